@@ -0,0 +1,40 @@
+//! Compares `dwithin` against the naive `distance <= dist` on a large ring
+//! whose nearest segment is far from the first one within range.
+//!
+//! Run with `cargo bench --bench dwithin`.
+
+use std::time::{Duration, Instant};
+
+use lwgeom::LWGeom;
+
+const ITERATIONS: u32 = 50;
+
+fn circle_polygon(vertices: usize) -> LWGeom {
+    let ring = (0..vertices)
+        .map(|i| {
+            let angle = i as f64 * core::f64::consts::TAU / vertices as f64;
+            format!("{} {}", 1000.0 * angle.cos(), 1000.0 * angle.sin())
+        })
+        .chain(core::iter::once("1000 0".to_owned()))
+        .collect::<Vec<_>>()
+        .join(",");
+    LWGeom::from_text(&format!("POLYGON(({ring}))"), None).unwrap()
+}
+
+fn time(f: impl Fn() -> bool) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(f());
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let polygon = circle_polygon(100_000);
+    let point = LWGeom::from_text("POINT(-1010 0)", None).unwrap();
+
+    let fast = time(|| polygon.dwithin(&point, 2000.0).unwrap());
+    let naive = time(|| polygon.distance(&point).unwrap() <= 2000.0);
+    println!("dwithin:  {fast:?}/iter");
+    println!("distance: {naive:?}/iter");
+}
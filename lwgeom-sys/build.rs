@@ -125,7 +125,19 @@ fn main() {
                 .to_string_lossy()
                 .into_owned(),
         )
+        .header(
+            liblwgeom_dst
+                .join("lwgeom_geos.h")
+                .to_string_lossy()
+                .into_owned(),
+        )
         .clang_arg(format!("-I{}", proj_lib.include_paths[0].display()))
+        .clang_args(
+            geos_lib
+                .include_paths
+                .iter()
+                .map(|path| format!("-I{}", path.display())),
+        )
         .ctypes_prefix("libc")
         .use_core()
         .generate()
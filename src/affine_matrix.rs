@@ -0,0 +1,104 @@
+use lwgeom_sys::*;
+
+/// 3D affine transform in `ST_Affine` order:
+///
+/// ```text
+/// x' = a*x + b*y + c*z + xoff
+/// y' = d*x + e*y + f*z + yoff
+/// z' = g*x + h*y + i*z + zoff
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AffineMatrix {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+    pub g: f64,
+    pub h: f64,
+    pub i: f64,
+    pub xoff: f64,
+    pub yoff: f64,
+    pub zoff: f64,
+}
+
+impl AffineMatrix {
+    pub fn identity() -> Self {
+        Self::scaling(1.0, 1.0, 1.0)
+    }
+
+    pub fn translation(dx: f64, dy: f64, dz: f64) -> Self {
+        AffineMatrix {
+            xoff: dx,
+            yoff: dy,
+            zoff: dz,
+            ..Self::identity()
+        }
+    }
+
+    pub fn scaling(sx: f64, sy: f64, sz: f64) -> Self {
+        AffineMatrix {
+            a: sx,
+            b: 0.0,
+            c: 0.0,
+            d: 0.0,
+            e: sy,
+            f: 0.0,
+            g: 0.0,
+            h: 0.0,
+            i: sz,
+            xoff: 0.0,
+            yoff: 0.0,
+            zoff: 0.0,
+        }
+    }
+
+    /// Counterclockwise rotation by `theta` radians about the Z axis.
+    pub fn rotation_z(theta: f64) -> Self {
+        let (sin, cos) = theta.sin_cos();
+        AffineMatrix {
+            a: cos,
+            b: -sin,
+            d: sin,
+            e: cos,
+            ..Self::identity()
+        }
+    }
+
+    /// Composes two transforms, applying `self` first and then `next`.
+    pub fn then(&self, next: &AffineMatrix) -> Self {
+        let n = next;
+        AffineMatrix {
+            a: n.a * self.a + n.b * self.d + n.c * self.g,
+            b: n.a * self.b + n.b * self.e + n.c * self.h,
+            c: n.a * self.c + n.b * self.f + n.c * self.i,
+            d: n.d * self.a + n.e * self.d + n.f * self.g,
+            e: n.d * self.b + n.e * self.e + n.f * self.h,
+            f: n.d * self.c + n.e * self.f + n.f * self.i,
+            g: n.g * self.a + n.h * self.d + n.i * self.g,
+            h: n.g * self.b + n.h * self.e + n.i * self.h,
+            i: n.g * self.c + n.h * self.f + n.i * self.i,
+            xoff: n.a * self.xoff + n.b * self.yoff + n.c * self.zoff + n.xoff,
+            yoff: n.d * self.xoff + n.e * self.yoff + n.f * self.zoff + n.yoff,
+            zoff: n.g * self.xoff + n.h * self.yoff + n.i * self.zoff + n.zoff,
+        }
+    }
+
+    pub(crate) fn to_affine(self) -> AFFINE {
+        AFFINE {
+            afac: self.a,
+            bfac: self.b,
+            cfac: self.c,
+            dfac: self.d,
+            efac: self.e,
+            ffac: self.f,
+            gfac: self.g,
+            hfac: self.h,
+            ifac: self.i,
+            xoff: self.xoff,
+            yoff: self.yoff,
+            zoff: self.zoff,
+        }
+    }
+}
@@ -0,0 +1,25 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinStyle {
+    Round = 1,
+    Mitre = 2,
+    Bevel = 3,
+}
+
+/// Buffer and offset settings, defaulting to the PostGIS
+/// `quad_segs=8 join=round mitre_limit=5.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BufferParams {
+    pub quad_segs: i32,
+    pub join_style: JoinStyle,
+    pub mitre_limit: f64,
+}
+
+impl Default for BufferParams {
+    fn default() -> Self {
+        BufferParams {
+            quad_segs: 8,
+            join_style: JoinStyle::Round,
+            mitre_limit: 5.0,
+        }
+    }
+}
@@ -16,4 +16,28 @@ pub enum LWGeomError {
     WKTParseError(String),
     #[error("failed to call function `{0}`, but no error message returned")]
     FailedWithoutMessageError(String),
+    #[error("liblwgeom error: {0}")]
+    LibError(String),
+    #[error("function `{0}` failed in GEOS: {1}")]
+    GEOSError(String, String),
+    #[error("invalid trajectory: {0}")]
+    InvalidTrajectoryError(#[from] TrajectoryError),
+    #[error("trajectories do not share a common time range")]
+    DisjointTrajectoriesError,
+    #[error("function `{0}` called with mixed SRIDs {1} and {2}")]
+    SRIDMismatchError(String, i32, i32),
+    #[error("function `{0}` is undefined for empty geometries")]
+    EmptyGeometryError(String),
+    #[error("function `{0}` expects lon/lat coordinates, got {1}")]
+    CoordinateOutOfRangeError(String, f64),
+}
+
+#[derive(Debug, Error)]
+pub enum TrajectoryError {
+    #[error("trajectory must be a LineString")]
+    NotLineStringError,
+    #[error("trajectory must have an M dimension")]
+    MissingMeasureError,
+    #[error("measure of vertex {0} is not bigger than the measure of the previous vertex")]
+    NonIncreasingMeasureError(usize),
 }
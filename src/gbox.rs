@@ -5,6 +5,9 @@ use core::marker::PhantomData;
 
 use lwgeom_sys::*;
 
+use crate::lwpoly::LWPoly;
+use crate::{LWGeom, lwerror};
+
 pub struct GBox(*mut GBOX);
 
 impl GBox {
@@ -13,6 +16,7 @@ impl GBox {
             !ptr.is_null(),
             "Attempted to create a GBox from a null pointer."
         );
+        lwerror::install();
         GBox(ptr)
     }
 
@@ -99,6 +103,18 @@ impl GBox {
     pub fn ymax(&self) -> f64 {
         self.as_ref().ymax
     }
+
+    pub fn width(&self) -> f64 {
+        self.xmax() - self.xmin()
+    }
+
+    pub fn height(&self) -> f64 {
+        self.ymax() - self.ymin()
+    }
+
+    pub fn area(&self) -> f64 {
+        self.width() * self.height()
+    }
 }
 
 impl GBoxRef {
@@ -117,4 +133,39 @@ impl GBoxRef {
     pub fn ymax(&self) -> f64 {
         self.as_ref().ymax
     }
+
+    pub fn width(&self) -> f64 {
+        self.xmax() - self.xmin()
+    }
+
+    pub fn height(&self) -> f64 {
+        self.ymax() - self.ymin()
+    }
+
+    pub fn area(&self) -> f64 {
+        self.width() * self.height()
+    }
+
+    /// Whether the point is inside the box or on its edges.
+    pub fn contains_point(&self, x: f64, y: f64) -> bool {
+        let point = POINT2D { x, y };
+        unsafe { gbox_contains_point2d(self.as_ptr(), &point) != 0 }
+    }
+
+    /// Whether the boxes overlap in 2D; touching edges count.
+    pub fn intersects(&self, other: &GBoxRef) -> bool {
+        unsafe { gbox_overlaps_2d(self.as_ptr(), other.as_ptr()) != 0 }
+    }
+
+    pub fn to_lwgeom(&self, srid: i32) -> LWGeom {
+        LWPoly::construct_envelope(srid, self.xmin(), self.ymin(), self.xmax(), self.ymax())
+            .into_lwgeom()
+    }
+
+    /// Copy of the box grown by `distance` on every side.
+    pub fn expand(&self, distance: f64) -> GBox {
+        let gbox = GBox::from_ptr(unsafe { gbox_copy(self.as_ptr()) });
+        unsafe { gbox_expand(gbox.as_ptr(), distance) };
+        gbox
+    }
 }
@@ -0,0 +1,40 @@
+use core::fmt;
+
+/// Header summary of a geometry, see [`crate::LWGeom::describe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeomInfo {
+    pub geom_type: String,
+    pub srid: Option<i32>,
+    pub has_z: bool,
+    pub has_m: bool,
+    pub is_geodetic: bool,
+    pub has_bbox: bool,
+    pub num_geometries: usize,
+    pub num_vertices: usize,
+}
+
+impl fmt::Display for GeomInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flags = [
+            (self.has_z, 'Z'),
+            (self.has_m, 'M'),
+            (self.has_bbox, 'B'),
+            (self.is_geodetic, 'G'),
+            (self.srid.is_some(), 'S'),
+        ];
+
+        write!(f, "{}", self.geom_type)?;
+        if flags.iter().any(|(set, _)| *set) {
+            write!(f, "[")?;
+            for (_, flag) in flags.iter().filter(|(set, _)| *set) {
+                write!(f, "{flag}")?;
+            }
+            write!(f, "]")?;
+        }
+        write!(
+            f,
+            " with {} geometries and {} vertices",
+            self.num_geometries, self.num_vertices
+        )
+    }
+}
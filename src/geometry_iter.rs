@@ -0,0 +1,38 @@
+use crate::LWGeomRef;
+
+pub struct GeometryIter<'a> {
+    geom: &'a LWGeomRef,
+    index: usize,
+    len: usize,
+}
+
+impl<'a> GeometryIter<'a> {
+    pub(crate) fn new(geom: &'a LWGeomRef) -> Self {
+        Self {
+            geom,
+            index: 0,
+            len: geom.num_geometries(),
+        }
+    }
+}
+
+impl<'a> Iterator for GeometryIter<'a> {
+    type Item = &'a LWGeomRef;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            return None;
+        }
+
+        let geom = self.geom.geometry_n(self.index);
+        self.index += 1;
+        geom
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for GeometryIter<'_> {}
@@ -0,0 +1,93 @@
+use core::ffi::CStr;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use libc::c_char;
+use lwgeom_sys::*;
+
+use crate::{LWGeom, LWGeomError, Result, lwerror};
+
+pub(crate) struct GEOSGeom(*mut GEOSGeometry);
+
+impl GEOSGeom {
+    pub fn from_lwgeom(geom: &LWGeom, function: &str) -> Result<Self> {
+        let p_geos = unsafe { LWGEOM2GEOS(geom.as_ptr(), 0) };
+        Self::from_ptr(p_geos, function)
+    }
+
+    pub fn from_ptr(ptr: *mut GEOSGeometry, function: &str) -> Result<Self> {
+        if ptr.is_null() {
+            return Err(error(function));
+        }
+
+        Ok(Self(ptr))
+    }
+
+    pub fn as_ptr(&self) -> *mut GEOSGeometry {
+        self.0
+    }
+
+    pub fn to_lwgeom(&self, srid: i32, want3d: bool, function: &str) -> Result<LWGeom> {
+        let p_geom = unsafe { GEOS2LWGEOM(self.as_ptr(), want3d as u8) };
+        if p_geom.is_null() {
+            return Err(error(function));
+        }
+
+        let mut geom = LWGeom::from_ptr(p_geom);
+        geom.set_srid(srid);
+        Ok(geom)
+    }
+}
+
+impl Drop for GEOSGeom {
+    fn drop(&mut self) {
+        unsafe { GEOSGeom_destroy(self.as_ptr()) }
+    }
+}
+
+static GEOS: Mutex<()> = Mutex::new(());
+
+/// Serializes access to the global GEOS context and to `lwgeom_geos_errmsg`,
+/// which liblwgeom shares between all threads, and resets both for the call.
+/// Hold the guard until every GEOS object and message is done with; that
+/// includes liblwgeom functions which call into GEOS themselves.
+pub(crate) fn lock() -> MutexGuard<'static, ()> {
+    let guard = GEOS.lock().unwrap_or_else(PoisonError::into_inner);
+    init();
+    guard
+}
+
+fn init() {
+    lwerror::init();
+    unsafe {
+        initGEOS(Some(lwnotice), Some(lwgeom_geos_error));
+        *core::ptr::addr_of_mut!(lwgeom_geos_errmsg).cast::<c_char>() = 0;
+    }
+}
+
+pub(crate) fn error(function: &str) -> LWGeomError {
+    let c_message = unsafe { CStr::from_ptr(core::ptr::addr_of!(lwgeom_geos_errmsg).cast()) };
+    if c_message.is_empty() {
+        // Conversions to and from GEOS report through lwerror instead.
+        lwerror::error(LWGeomError::FailedWithoutMessageError(function.to_owned()))
+    } else {
+        LWGeomError::GEOSError(
+            function.to_owned(),
+            c_message.to_string_lossy().into_owned(),
+        )
+    }
+}
+
+/// Runs a GEOS binary predicate, which returns 2 on exception.
+pub(crate) fn predicate(
+    geom1: &LWGeom, geom2: &LWGeom, function: &str,
+    predicate: unsafe extern "C" fn(*const GEOSGeometry, *const GEOSGeometry) -> c_char,
+) -> Result<bool> {
+    let _geos = lock();
+    let g1 = GEOSGeom::from_lwgeom(geom1, function)?;
+    let g2 = GEOSGeom::from_lwgeom(geom2, function)?;
+    match unsafe { predicate(g1.as_ptr(), g2.as_ptr()) } {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(error(function)),
+    }
+}
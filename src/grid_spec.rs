@@ -0,0 +1,36 @@
+use lwgeom_sys::*;
+
+/// Grid origin and cell size per ordinate; a size of 0 leaves that ordinate
+/// unsnapped.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GridSpec {
+    pub origin_x: f64,
+    pub origin_y: f64,
+    pub origin_z: f64,
+    pub origin_m: f64,
+    pub size_x: f64,
+    pub size_y: f64,
+    pub size_z: f64,
+    pub size_m: f64,
+}
+
+impl GridSpec {
+    pub(crate) fn is_valid(&self) -> bool {
+        [self.size_x, self.size_y, self.size_z, self.size_m]
+            .iter()
+            .all(|size| *size >= 0.0)
+    }
+
+    pub(crate) fn to_gridspec(self) -> gridspec {
+        gridspec {
+            ipx: self.origin_x,
+            ipy: self.origin_y,
+            ipz: self.origin_z,
+            ipm: self.origin_m,
+            xsize: self.size_x,
+            ysize: self.size_y,
+            zsize: self.size_z,
+            msize: self.size_m,
+        }
+    }
+}
@@ -1,12 +1,38 @@
+mod affine_matrix;
+mod buffer_params;
 mod error;
 mod gbox;
+mod geom_info;
+mod geometry_iter;
+mod geos;
+mod grid_spec;
+mod lwerror;
 mod lwgeom;
 mod lwgeom_parser_result;
+mod lwline;
+mod lwpoint;
 mod lwpoly;
+mod ordinate;
+mod sfs_version;
+mod spheroid;
+mod vertex_iter;
 
-pub use error::{LWGeomError, Result};
+pub use affine_matrix::AffineMatrix;
+pub use buffer_params::{BufferParams, JoinStyle};
+pub use error::{LWGeomError, Result, TrajectoryError};
 pub use gbox::{GBox, GBoxRef};
-pub use lwgeom::{LWGeom, LWGeomRef};
+pub use geom_info::GeomInfo;
+pub use geometry_iter::GeometryIter;
+pub use grid_spec::GridSpec;
+pub use lwerror::last_error;
+pub use lwgeom::{LWGeom, LWGeomRef, angle};
+pub use lwline::LWLineRef;
+pub use lwpoint::LWPointRef;
+pub use lwpoly::LWPolyRef;
+pub use ordinate::Ordinate;
+pub use sfs_version::SfsVersion;
+pub use spheroid::Spheroid;
+pub use vertex_iter::VertexIter;
 
 #[cfg(test)]
 mod tests {
@@ -62,4 +88,2103 @@ mod tests {
             "POLYGON((-10018754.1713945 0,-10018754.1713945 10018754.1713945,0 10018754.1713945,0 0,-10018754.1713945 0))"
         );
     }
+
+    #[test]
+    fn test_snap() {
+        let result = LWGeom::from_text("LINESTRING(0 0,10 0.05)", None)
+            .unwrap()
+            .snap(
+                &LWGeom::from_text("LINESTRING(0 0,10 0)", None).unwrap(),
+                0.1,
+            )
+            .unwrap()
+            .as_text(None)
+            .unwrap();
+        assert_eq!(result, "LINESTRING(0 0,10 0)");
+    }
+
+    #[test]
+    fn test_dimensionality() {
+        for (ewkt, text, has_z, has_m, ndims) in [
+            ("POINT(1 2)", "POINT(1 2)", false, false, 2),
+            ("POINT(1 2 3)", "POINT Z (1 2 3)", true, false, 3),
+            ("POINTM(1 2 3)", "POINT M (1 2 3)", false, true, 3),
+            ("POINT(1 2 3 4)", "POINT ZM (1 2 3 4)", true, true, 4),
+        ] {
+            let geom = LWGeom::from_ewkt(ewkt).unwrap();
+            assert_eq!(geom.has_z(), has_z);
+            assert_eq!(geom.has_m(), has_m);
+            assert_eq!(geom.is_3d(), has_z);
+            assert_eq!(geom.is_measured(), has_m);
+            assert_eq!(geom.ndims(), ndims);
+            assert_eq!(geom.as_ewkt(None).unwrap(), ewkt);
+            assert_eq!(geom.as_text(None).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn test_node() {
+        let result = LWGeom::from_text("MULTILINESTRING((0 0,10 10),(0 10,10 0))", None)
+            .unwrap()
+            .node()
+            .unwrap()
+            .as_text(None)
+            .unwrap();
+        assert!(result.starts_with("MULTILINESTRING("));
+        assert_eq!(result.matches('(').count(), 5);
+        assert_eq!(result.matches("5 5").count(), 4);
+    }
+
+    #[test]
+    fn test_line_interpolate_point() {
+        let line = LWGeom::from_text("LINESTRING(0 0,10 0)", None).unwrap();
+        let result = line
+            .line_interpolate_point(0.5, false)
+            .unwrap()
+            .as_text(None)
+            .unwrap();
+        assert_eq!(result, "POINT(5 0)");
+
+        let result = line
+            .line_interpolate_point(0.25, true)
+            .unwrap()
+            .as_text(None)
+            .unwrap();
+        assert_eq!(result, "MULTIPOINT(2.5 0,5 0,7.5 0,10 0)");
+
+        assert!(line.line_interpolate_point(1.5, false).is_err());
+    }
+
+    #[test]
+    fn test_count_vertices() {
+        let geom = LWGeom::from_text(
+            "MULTIPOLYGON(((0 0,10 0,10 10,0 10,0 0),(2 2,4 2,4 4,2 2)),((20 20,30 20,30 30,20 20)))",
+            None,
+        )
+        .unwrap();
+        assert_eq!(geom.count_vertices(), 13);
+        assert_eq!(
+            LWGeom::from_text("POLYGON EMPTY", None)
+                .unwrap()
+                .count_vertices(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_line_locate_point() {
+        let line = LWGeom::from_text("LINESTRING(0 0,10 0)", None).unwrap();
+        let result = line
+            .line_locate_point(&LWGeom::from_text("POINT(5 0)", None).unwrap())
+            .unwrap();
+        assert!((result - 0.5).abs() < 1e-9);
+
+        let result = line
+            .line_locate_point(&LWGeom::from_text("POINT(2 3)", None).unwrap())
+            .unwrap();
+        assert!((result - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_geometry_n() {
+        let pieces = LWGeom::from_text(
+            "MULTILINESTRING((10 10,190 190), (15 15,30 30,100 90))",
+            None,
+        )
+        .unwrap()
+        .split(&LWGeom::from_text("POINT(30 30)", None).unwrap());
+        assert_eq!(pieces.num_geometries(), 4);
+        assert_eq!(
+            pieces.geometry_n(1).unwrap().as_text(None).unwrap(),
+            "LINESTRING(30 30,190 190)"
+        );
+        assert!(pieces.geometry_n(4).is_none());
+        assert_eq!((&pieces).into_iter().count(), 4);
+
+        let point = LWGeom::from_text("POINT(1 2)", None).unwrap();
+        assert_eq!(point.num_geometries(), 1);
+        assert_eq!(
+            point.geometry_n(0).unwrap().as_text(None).unwrap(),
+            "POINT(1 2)"
+        );
+
+        let empty = LWGeom::from_text("GEOMETRYCOLLECTION EMPTY", None).unwrap();
+        assert_eq!(empty.num_geometries(), 0);
+        assert!(empty.geometry_n(0).is_none());
+    }
+
+    #[test]
+    fn test_line_substring() {
+        let line = LWGeom::from_text("LINESTRING(0 0,3 0)", None).unwrap();
+        let result = line
+            .line_substring(1.0 / 3.0, 2.0 / 3.0)
+            .unwrap()
+            .as_text(None)
+            .unwrap();
+        assert_eq!(result, "LINESTRING(1 0,2 0)");
+
+        assert!(line.line_substring(0.6, 0.4).is_err());
+        assert!(line.line_substring(-0.1, 0.4).is_err());
+    }
+
+    #[test]
+    fn test_start_point_end_point() {
+        let line = LWGeom::from_ewkt("SRID=4326;LINESTRING(1 2 3,4 5 6,7 8 9)").unwrap();
+        let start = line.start_point().unwrap();
+        drop(line);
+        assert_eq!(start.as_ewkt(None).unwrap(), "SRID=4326;POINT(1 2 3)");
+
+        let arc = LWGeom::from_text("CIRCULARSTRING(0 0,1 1,2 0)", None).unwrap();
+        assert_eq!(
+            arc.end_point().unwrap().as_text(None).unwrap(),
+            "POINT(2 0)"
+        );
+
+        assert!(
+            LWGeom::from_text("LINESTRING EMPTY", None)
+                .unwrap()
+                .end_point()
+                .is_none()
+        );
+        assert!(
+            LWGeom::from_text("POINT(1 2)", None)
+                .unwrap()
+                .start_point()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_closest_point() {
+        let line = LWGeom::from_text("LINESTRING(0 0,10 0)", None).unwrap();
+        let result = line
+            .closest_point(&LWGeom::from_text("POINT(5 3)", None).unwrap())
+            .unwrap();
+        assert_eq!(result.as_text(None).unwrap(), "POINT(5 0)");
+        assert_eq!(line.line_locate_point(&result).unwrap(), 0.5);
+    }
+
+    #[test]
+    fn test_point_n() {
+        let line = LWGeom::from_text("LINESTRING ZM (1 2 3 4,5 6 7 8,9 10 11 12)", None).unwrap();
+        assert_eq!(
+            line.point_n(1).unwrap().as_text(None).unwrap(),
+            "POINT ZM (1 2 3 4)"
+        );
+        assert_eq!(
+            line.point_n(-1).unwrap().as_text(None).unwrap(),
+            "POINT ZM (9 10 11 12)"
+        );
+        assert!(line.point_n(0).is_none());
+        assert!(line.point_n(4).is_none());
+        assert!(line.point_n(-4).is_none());
+    }
+
+    #[test]
+    fn test_shortest_line() {
+        let line = LWGeom::from_text("LINESTRING(0 0,10 0)", None).unwrap();
+        let point = LWGeom::from_text("POINT(5 3)", None).unwrap();
+        let result = line.shortest_line(&point).unwrap();
+        assert_eq!(result.as_text(None).unwrap(), "LINESTRING(5 0,5 3)");
+        assert_eq!(
+            result.start_point().unwrap().as_text(None).unwrap(),
+            line.closest_point(&point).unwrap().as_text(None).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_is_closed_is_ring() {
+        let ring = LWGeom::from_text("LINESTRING(0 0,1 0,1 1,0 1,0 0)", None).unwrap();
+        assert!(ring.is_closed());
+        assert!(ring.is_ring().unwrap());
+
+        let bowtie = LWGeom::from_text("LINESTRING(0 0,1 1,1 0,0 1,0 0)", None).unwrap();
+        assert!(bowtie.is_closed());
+        assert!(!bowtie.is_ring().unwrap());
+
+        let open = LWGeom::from_text("LINESTRING Z (0 0 0,1 0 0,0 0 1)", None).unwrap();
+        assert!(!open.is_closed());
+        assert!(!open.is_ring().unwrap());
+
+        let empty = LWGeom::from_text("LINESTRING EMPTY", None).unwrap();
+        assert!(!empty.is_closed());
+        assert!(!empty.is_ring().unwrap());
+
+        assert!(
+            LWGeom::from_text("POINT(1 2)", None)
+                .unwrap()
+                .is_ring()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_hausdorff_distance() {
+        let result = LWGeom::from_text("LINESTRING(0 0,10 0)", None)
+            .unwrap()
+            .hausdorff_distance(&LWGeom::from_text("LINESTRING(0 2,10 2)", None).unwrap())
+            .unwrap();
+        assert_eq!(result, 2.0);
+
+        let a = LWGeom::from_text("LINESTRING(130 0,0 0,0 150)", None).unwrap();
+        let b = LWGeom::from_text("LINESTRING(10 10,10 150,130 10)", None).unwrap();
+        assert!((a.hausdorff_distance(&b).unwrap() - 14.142135623730951).abs() < 1e-9);
+        assert!((a.hausdorff_distance_densify(&b, 0.5).unwrap() - 70.0).abs() < 1e-9);
+
+        let c = LWGeom::from_text("LINESTRING(0 2,10 2)", Some(4326)).unwrap();
+        assert!(matches!(
+            a.hausdorff_distance(&c),
+            Err(LWGeomError::SRIDMismatchError(_, 0, 4326))
+        ));
+        assert!(matches!(
+            a.hausdorff_distance_densify(&c, 0.5),
+            Err(LWGeomError::SRIDMismatchError(_, 0, 4326))
+        ));
+    }
+
+    #[test]
+    fn test_mem_size() {
+        let point = LWGeom::from_text("POINT(1 2)", None).unwrap();
+        let multi = LWGeom::from_text("MULTIPOINT(1 2,3 4)", None).unwrap();
+        let member_sizes: usize = multi.into_iter().map(LWGeomRef::mem_size).sum();
+        assert!(point.mem_size() > 2 * core::mem::size_of::<f64>());
+        assert!(multi.mem_size() > member_sizes);
+        assert!(member_sizes >= 2 * point.mem_size());
+    }
+
+    #[test]
+    fn test_frechet_distance() {
+        let a = LWGeom::from_text("LINESTRING(0 0,100 0)", None).unwrap();
+        let b = LWGeom::from_text("LINESTRING(0 0,50 50,100 0)", None).unwrap();
+        assert!((a.frechet_distance(&b, None).unwrap() - 70.71067811865476).abs() < 1e-9);
+        assert!((a.frechet_distance(&b, Some(0.5)).unwrap() - 50.0).abs() < 1e-9);
+        assert!(a.frechet_distance(&b, Some(0.0)).is_err());
+
+        let c = LWGeom::from_text("LINESTRING(0 0,100 0)", Some(3857)).unwrap();
+        assert!(matches!(
+            a.frechet_distance(&c, None),
+            Err(LWGeomError::SRIDMismatchError(_, 0, 3857))
+        ));
+    }
+
+    #[test]
+    fn test_dimension() {
+        for (wkt, dimension) in [
+            ("POINT(1 2)", Some(0)),
+            ("MULTILINESTRING((0 0,1 1))", Some(1)),
+            ("POLYGON((0 0,1 0,1 1,0 0))", Some(2)),
+            (
+                "GEOMETRYCOLLECTION(POINT(1 2),LINESTRING(0 0,1 1))",
+                Some(1),
+            ),
+            ("POINT EMPTY", None),
+            ("GEOMETRYCOLLECTION EMPTY", None),
+        ] {
+            assert_eq!(LWGeom::from_text(wkt, None).unwrap().dimension(), dimension);
+        }
+    }
+
+    #[test]
+    fn test_minimum_bounding_circle() {
+        let points = LWGeom::from_text("MULTIPOINT(0 0,10 0,5 3)", None).unwrap();
+        let (center, radius) = points.minimum_bounding_radius().unwrap();
+        assert_eq!(center.as_text(None).unwrap(), "POINT(5 0)");
+        assert!((radius - 5.0).abs() < 1e-9);
+
+        let circle = points.minimum_bounding_circle(8).unwrap();
+        let bbox = circle.get_bbox_ref();
+        assert!((bbox.xmin() - 0.0).abs() < 1e-9);
+        assert!((bbox.xmax() - 10.0).abs() < 1e-9);
+        assert!((bbox.ymax() - 5.0).abs() < 1e-9);
+        assert_eq!(circle.count_vertices(), 33);
+    }
+
+    #[test]
+    fn test_envelope() {
+        let result = LWGeom::from_ewkt("SRID=4326;LINESTRING(1 2,3 4,5 0)")
+            .unwrap()
+            .envelope()
+            .unwrap()
+            .as_ewkt(None)
+            .unwrap();
+        assert_eq!(result, "SRID=4326;POLYGON((1 0,1 4,5 4,5 0,1 0))");
+
+        let result = LWGeom::from_text("LINESTRING(1 2,1 5)", None)
+            .unwrap()
+            .envelope()
+            .unwrap()
+            .as_text(None)
+            .unwrap();
+        assert_eq!(result, "LINESTRING(1 2,1 5)");
+
+        let result = LWGeom::from_text("MULTIPOINT(1 2,1 2)", None)
+            .unwrap()
+            .envelope()
+            .unwrap()
+            .as_text(None)
+            .unwrap();
+        assert_eq!(result, "POINT(1 2)");
+
+        let result = LWGeom::from_text("LINESTRING EMPTY", None)
+            .unwrap()
+            .envelope()
+            .unwrap()
+            .as_text(None)
+            .unwrap();
+        assert_eq!(result, "LINESTRING EMPTY");
+    }
+
+    #[test]
+    fn test_oriented_envelope() {
+        let result = LWGeom::from_text("MULTIPOINT(0 0,10 10,9 11,-1 1,5 5)", None)
+            .unwrap()
+            .oriented_envelope()
+            .unwrap();
+        let expected = LWGeom::from_text("POLYGON((0 0,10 10,9 11,-1 1,0 0))", None).unwrap();
+        assert_eq!(result.count_vertices(), 5);
+        assert!(result.hausdorff_distance(&expected).unwrap() < 1e-9);
+    }
+
+    #[test]
+    fn test_boundary() {
+        let result = LWGeom::from_ewkt("SRID=4326;LINESTRING(0 0,5 5,10 0)")
+            .unwrap()
+            .boundary()
+            .unwrap()
+            .as_ewkt(None)
+            .unwrap();
+        assert_eq!(result, "SRID=4326;MULTIPOINT(0 0,10 0)");
+
+        let result =
+            LWGeom::from_text("POLYGON((0 0,10 0,10 10,0 10,0 0),(2 2,4 2,4 4,2 2))", None)
+                .unwrap()
+                .boundary()
+                .unwrap()
+                .as_text(None)
+                .unwrap();
+        assert_eq!(
+            result,
+            "MULTILINESTRING((0 0,10 0,10 10,0 10,0 0),(2 2,4 2,4 4,2 2))"
+        );
+
+        for wkt in ["POINT(1 2)", "LINESTRING(0 0,1 0,1 1,0 0)"] {
+            assert!(
+                LWGeom::from_text(wkt, None)
+                    .unwrap()
+                    .boundary()
+                    .unwrap()
+                    .is_empty()
+            );
+        }
+    }
+
+    #[test]
+    fn test_flip_coordinates() {
+        let point = LWGeom::from_text("POINT(1 2)", None).unwrap();
+        let result = point.flip_coordinates();
+        assert_eq!(result.as_text(None).unwrap(), "POINT(2 1)");
+        assert_eq!(point.as_text(None).unwrap(), "POINT(1 2)");
+
+        let line = LWGeom::from_text("LINESTRING(1 2,3 6)", None).unwrap();
+        assert_eq!(line.get_bbox_ref().to_string(), "GBOX((1,2),(3,6))");
+        assert_eq!(
+            line.flip_coordinates().get_bbox_ref().to_string(),
+            "GBOX((2,1),(6,3))"
+        );
+    }
+
+    #[test]
+    fn test_centroid() {
+        for (wkt, x, y) in [
+            (
+                "POLYGON((0 0,10 0,10 10,0 10,0 0),(2 2,4 2,4 4,2 4,2 2))",
+                488.0 / 96.0,
+                488.0 / 96.0,
+            ),
+            ("MULTIPOINT(0 0,4 0,2 6)", 2.0, 2.0),
+            ("POLYGON((0 0,10 0,5 0,0 0))", 5.0, 0.0),
+            (
+                "GEOMETRYCOLLECTION(POINT(0 0),POLYGON((1 1,3 1,3 3,1 3,1 1)))",
+                2.0,
+                2.0,
+            ),
+        ] {
+            let centroid = LWGeom::from_ewkt(&format!("SRID=3857;{wkt}"))
+                .unwrap()
+                .centroid()
+                .unwrap();
+            assert_eq!(centroid.get_srid(), Some(3857));
+            let bbox = centroid.get_bbox_ref();
+            assert!((bbox.xmin() - x).abs() < 1e-9, "{wkt}");
+            assert!((bbox.ymin() - y).abs() < 1e-9, "{wkt}");
+        }
+
+        assert!(
+            LWGeom::from_text("POLYGON EMPTY", None)
+                .unwrap()
+                .centroid()
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_num_geometries() {
+        for (wkt, count) in [
+            ("MULTIPOINT(0 0,1 1,2 2)", 3),
+            ("POINT(1 2)", 1),
+            ("GEOMETRYCOLLECTION(POINT(1 2),LINESTRING(0 0,1 1))", 2),
+            ("MULTIPOLYGON EMPTY", 0),
+        ] {
+            assert_eq!(
+                LWGeom::from_text(wkt, None).unwrap().num_geometries(),
+                count
+            );
+        }
+    }
+
+    #[test]
+    fn test_geometry_n_multilinestring() {
+        let lines =
+            LWGeom::from_text("MULTILINESTRING((0 0,1 1),(2 2,3 3),(4 4,5 5))", None).unwrap();
+        let second = lines.geometry_n(1).unwrap();
+        assert_eq!(second.as_text(None).unwrap(), "LINESTRING(2 2,3 3)");
+        assert_eq!(second.num_geometries(), 1);
+        assert!(lines.geometry_n(3).is_none());
+        assert!(lines.geometry_n(usize::MAX).is_none());
+    }
+
+    #[test]
+    fn test_validate_trajectory() {
+        let track = LWGeom::from_text("LINESTRING M (0 0 1,1 1 2,2 2 3)", None).unwrap();
+        assert!(track.is_trajectory());
+        assert!(track.validate_trajectory().is_ok());
+
+        let stalled = LWGeom::from_text("LINESTRING M (0 0 1,1 1 2,2 2 2)", None).unwrap();
+        assert!(!stalled.is_trajectory());
+        assert!(matches!(
+            stalled.validate_trajectory(),
+            Err(TrajectoryError::NonIncreasingMeasureError(2))
+        ));
+
+        let flat = LWGeom::from_text("LINESTRING(0 0,1 1)", None).unwrap();
+        assert!(!flat.is_trajectory());
+        assert!(matches!(
+            flat.validate_trajectory(),
+            Err(TrajectoryError::MissingMeasureError)
+        ));
+
+        let point = LWGeom::from_text("POINT M (0 0 1)", None).unwrap();
+        assert!(!point.is_trajectory());
+        assert!(matches!(
+            point.validate_trajectory(),
+            Err(TrajectoryError::NotLineStringError)
+        ));
+    }
+
+    #[test]
+    fn test_tcpa() {
+        let a = LWGeom::from_text("LINESTRING M (0 0 0,10 0 10)", None).unwrap();
+        let b = LWGeom::from_text("LINESTRING M (5 -4 0,5 6 10)", None).unwrap();
+        let (time, distance) = a.tcpa(&b).unwrap();
+        assert!((time - 4.5).abs() < 1e-9);
+        assert!((distance - 0.5f64.sqrt()).abs() < 1e-9);
+        assert!(a.cpa_within(&b, 1.0).unwrap());
+        assert!(!a.cpa_within(&b, 0.5).unwrap());
+
+        let later = LWGeom::from_text("LINESTRING M (0 0 20,1 1 30)", None).unwrap();
+        assert!(matches!(
+            a.tcpa(&later),
+            Err(LWGeomError::DisjointTrajectoriesError)
+        ));
+        assert!(matches!(
+            a.cpa_within(&later, 100.0),
+            Err(LWGeomError::DisjointTrajectoriesError)
+        ));
+
+        let flat = LWGeom::from_text("LINESTRING(0 0,1 1)", None).unwrap();
+        assert!(matches!(
+            a.tcpa(&flat),
+            Err(LWGeomError::InvalidTrajectoryError(
+                TrajectoryError::MissingMeasureError
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_downcast() {
+        let polygon =
+            LWGeom::from_text("POLYGON((0 0,10 0,10 10,0 0),(1 1,2 1,2 2,1 1))", None).unwrap();
+        assert_eq!(polygon.as_polygon().unwrap().num_rings(), 2);
+        assert!(polygon.as_point().is_none());
+        assert!(polygon.as_line().is_none());
+
+        let point = LWGeom::from_text("POINT(1 2)", None).unwrap();
+        assert!(!point.as_point().unwrap().is_empty());
+        assert!(point.as_polygon().is_none());
+
+        let lines = LWGeom::from_text("MULTILINESTRING((0 0,1 1,2 2))", None).unwrap();
+        assert!(lines.as_line().is_none());
+        assert_eq!(
+            lines.geometry_n(0).unwrap().as_line().unwrap().num_points(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_describe() {
+        let geom = LWGeom::from_ewkt("SRID=4326;MULTIPOINT Z(0 0 1,1 1 2)").unwrap();
+        let info = geom.describe();
+        assert_eq!(info.geom_type, "MultiPoint");
+        assert_eq!(info.srid, Some(4326));
+        assert!(info.has_z);
+        assert!(!info.has_m);
+        assert!(!info.is_geodetic);
+        assert_eq!(info.num_geometries, 2);
+        assert_eq!(info.num_vertices, 2);
+
+        let summary = geom.describe().to_string();
+        assert!(summary.starts_with("MultiPoint[Z"));
+        assert!(summary.ends_with("S] with 2 geometries and 2 vertices"));
+    }
+
+    #[test]
+    fn test_point_coordinates() {
+        let geom = LWGeom::from_text("POINT ZM(1 2 3 4)", None).unwrap();
+        let point = geom.as_point().unwrap();
+        assert_eq!(point.x(), Some(1.0));
+        assert_eq!(point.y(), Some(2.0));
+        assert_eq!(point.z(), Some(3.0));
+        assert_eq!(point.m(), Some(4.0));
+
+        let geom = LWGeom::from_text("POINT(1 2)", None).unwrap();
+        let point = geom.as_point().unwrap();
+        assert_eq!((point.x(), point.y()), (Some(1.0), Some(2.0)));
+        assert_eq!((point.z(), point.m()), (None, None));
+
+        let geom = LWGeom::from_text("POINT EMPTY", None).unwrap();
+        assert_eq!(geom.as_point().unwrap().x(), None);
+    }
+
+    #[test]
+    fn test_area() {
+        let polygon = LWGeom::from_text(
+            "POLYGON((0 0,10 0,10 10,0 10,0 0),(2 2,4 2,4 4,2 4,2 2))",
+            None,
+        )
+        .unwrap();
+        assert_eq!(polygon.area(), 96.0);
+
+        let collection = LWGeom::from_text(
+            "GEOMETRYCOLLECTION(POINT(0 0),LINESTRING(0 0,1 1),POLYGON((0 0,1 0,1 1,0 1,0 0)))",
+            None,
+        )
+        .unwrap();
+        assert_eq!(collection.area(), 1.0);
+        assert_eq!(
+            LWGeom::from_text("LINESTRING(0 0,1 1)", None)
+                .unwrap()
+                .area(),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_start_point_end_point_non_lines() {
+        let line = LWGeom::from_text("LINESTRING(0 0,1 1,2 2)", None).unwrap();
+        assert_eq!(
+            line.start_point().unwrap().as_text(None).unwrap(),
+            "POINT(0 0)"
+        );
+        assert_eq!(
+            line.end_point().unwrap().as_text(None).unwrap(),
+            "POINT(2 2)"
+        );
+
+        for wkt in [
+            "POLYGON((0 0,1 0,1 1,0 0))",
+            "MULTILINESTRING((0 0,1 1))",
+            "GEOMETRYCOLLECTION(LINESTRING(0 0,1 1))",
+        ] {
+            let geom = LWGeom::from_text(wkt, None).unwrap();
+            assert!(geom.start_point().is_none());
+            assert!(geom.end_point().is_none());
+        }
+    }
+
+    #[test]
+    fn test_point_n_negative_index() {
+        let line = LWGeom::from_text("LINESTRING(0 0,1 1,2 2,3 3)", None).unwrap();
+        assert_eq!(
+            line.point_n(2).unwrap().as_text(None).unwrap(),
+            "POINT(1 1)"
+        );
+        assert_eq!(
+            line.point_n(-2).unwrap().as_text(None).unwrap(),
+            "POINT(2 2)"
+        );
+        assert_eq!(
+            line.point_n(-4).unwrap().as_text(None).unwrap(),
+            line.start_point().unwrap().as_text(None).unwrap()
+        );
+        assert!(line.point_n(-5).is_none());
+        assert!(
+            LWGeom::from_text("POINT(1 1)", None)
+                .unwrap()
+                .point_n(1)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_perimeter() {
+        let polygon = LWGeom::from_text(
+            "POLYGON((0 0,10 0,10 10,0 10,0 0),(2 2,4 2,4 4,2 4,2 2))",
+            None,
+        )
+        .unwrap();
+        assert_eq!(polygon.perimeter(), 48.0);
+        assert_eq!(
+            LWGeom::from_text("LINESTRING(0 0,1 1)", None)
+                .unwrap()
+                .perimeter(),
+            0.0
+        );
+
+        let polygon = LWGeom::from_text("POLYGON Z((0 0 0,3 0 4,3 3 4,0 0 0))", None).unwrap();
+        assert!((polygon.perimeter() - (6.0 + 18f64.sqrt())).abs() < 1e-9);
+        assert!((polygon.perimeter_3d() - (8.0 + 34f64.sqrt())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_is_closed_polygon() {
+        let polygon = LWGeom::from_text("POLYGON((0 0,1 0,1 1,0 0))", None).unwrap();
+        assert!(polygon.is_closed());
+
+        let boundary = polygon.boundary().unwrap();
+        assert!(boundary.is_closed());
+
+        let lines = LWGeom::from_text("MULTILINESTRING((0 0,1 1,0 0),(0 0,1 1))", None).unwrap();
+        assert!(!lines.is_closed());
+    }
+
+    #[test]
+    fn test_length() {
+        let line = LWGeom::from_text("LINESTRING Z(0 0 0,3 4 12)", None).unwrap();
+        assert_eq!(line.length(), 5.0);
+        assert_eq!(line.length_3d(), 13.0);
+
+        let lines = LWGeom::from_text("MULTILINESTRING((0 0,3 4),(0 0,0 2))", None).unwrap();
+        assert_eq!(lines.length(), 7.0);
+        assert_eq!(lines.length_3d(), 7.0);
+
+        let arc = LWGeom::from_text("CIRCULARSTRING(-1 0,0 1,1 0)", None).unwrap();
+        assert!((arc.length() - core::f64::consts::PI).abs() < 1e-9);
+
+        let polygon = LWGeom::from_text("POLYGON((0 0,1 0,1 1,0 0))", None).unwrap();
+        assert_eq!(polygon.length(), 0.0);
+    }
+
+    #[test]
+    fn test_boundary_single_ring() {
+        let boundary = LWGeom::from_text("POLYGON((0 0,10 0,10 10,0 10,0 0))", None)
+            .unwrap()
+            .boundary()
+            .unwrap();
+        assert_eq!(
+            boundary.as_text(None).unwrap(),
+            "LINESTRING(0 0,10 0,10 10,0 10,0 0)"
+        );
+        assert!(boundary.is_ring().unwrap());
+    }
+
+    #[test]
+    fn test_distance() {
+        let point = LWGeom::from_text("POINT(0 0)", None).unwrap();
+        let line = LWGeom::from_text("LINESTRING(3 -1,3 1)", None).unwrap();
+        assert_eq!(point.distance(&line).unwrap(), 3.0);
+
+        let polygon = LWGeom::from_text("POLYGON((-1 -1,1 -1,1 1,-1 1,-1 -1))", None).unwrap();
+        assert_eq!(point.distance(&polygon).unwrap(), 0.0);
+        assert_eq!(polygon.distance(&line).unwrap(), 2.0);
+
+        let other = LWGeom::from_text("POINT(0 0)", Some(4326)).unwrap();
+        assert!(matches!(
+            point.distance(&other),
+            Err(LWGeomError::SRIDMismatchError(_, 0, 4326))
+        ));
+
+        let empty = LWGeom::from_text("POINT EMPTY", None).unwrap();
+        assert!(matches!(
+            point.distance(&empty),
+            Err(LWGeomError::EmptyGeometryError(_))
+        ));
+    }
+
+    #[test]
+    fn test_envelope_matches_bbox() {
+        let line = LWGeom::from_text("LINESTRING(-3 2,4 7,1 -5)", None).unwrap();
+        let envelope = line.envelope().unwrap();
+        let (bbox, envelope_bbox) = (line.get_bbox_ref(), envelope.get_bbox_ref());
+        assert_eq!(
+            (bbox.xmin(), bbox.ymin(), bbox.xmax(), bbox.ymax()),
+            (
+                envelope_bbox.xmin(),
+                envelope_bbox.ymin(),
+                envelope_bbox.xmax(),
+                envelope_bbox.ymax()
+            )
+        );
+        assert_eq!(envelope.area(), 84.0);
+
+        let point = LWGeom::from_text("POINT(1 2)", None).unwrap();
+        assert_eq!(
+            point.envelope().unwrap().as_text(None).unwrap(),
+            "POINT(1 2)"
+        );
+    }
+
+    #[test]
+    fn test_max_distance() {
+        let rectangle = LWGeom::from_text("POLYGON((0 0,3 0,3 4,0 4,0 0))", None).unwrap();
+        assert_eq!(rectangle.max_distance(&rectangle).unwrap(), 5.0);
+
+        let point = LWGeom::from_text("POINT(0 -2)", None).unwrap();
+        assert_eq!(point.max_distance(&rectangle).unwrap(), 45f64.sqrt());
+
+        let other = LWGeom::from_text("POINT(0 0)", Some(3857)).unwrap();
+        assert!(matches!(
+            rectangle.max_distance(&other),
+            Err(LWGeomError::SRIDMismatchError(_, 0, 3857))
+        ));
+    }
+
+    #[test]
+    fn test_distance_3d() {
+        let mast = LWGeom::from_text("LINESTRING Z(0 0 0,0 0 10)", None).unwrap();
+        let above = LWGeom::from_text("POINT Z(0 0 15)", None).unwrap();
+        assert_eq!(mast.distance_3d(&above).unwrap(), 5.0);
+        assert_eq!(mast.distance(&above).unwrap(), 0.0);
+
+        let wire = LWGeom::from_text("LINESTRING Z(-5 0 3,5 0 3)", None).unwrap();
+        let cable = LWGeom::from_text("LINESTRING Z(0 -5 7,0 5 7)", None).unwrap();
+        assert_eq!(wire.distance_3d(&cable).unwrap(), 4.0);
+
+        let flat = LWGeom::from_text("POINT(3 4)", None).unwrap();
+        assert_eq!(above.distance_3d(&flat).unwrap(), 5.0);
+
+        let other = LWGeom::from_text("POINT Z(0 0 0)", Some(4326)).unwrap();
+        assert!(matches!(
+            mast.distance_3d(&other),
+            Err(LWGeomError::SRIDMismatchError(_, 0, 4326))
+        ));
+    }
+
+    #[test]
+    fn test_transform() {
+        let geom = LWGeom::from_text("POINT(-71.064544 42.28787)", Some(4326))
+            .unwrap()
+            .transform(3857)
+            .unwrap();
+        assert_eq!(geom.get_srid(), Some(3857));
+        let point = geom.as_point().unwrap();
+        assert!((point.x().unwrap() - -7910868.851536).abs() < 1e-3);
+        assert!((point.y().unwrap() - 5204198.989922).abs() < 1e-3);
+
+        assert!(
+            LWGeom::from_text("POINT(0 0)", None)
+                .unwrap()
+                .transform(3857)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_with_srid() {
+        let geom = LWGeom::from_text("LINESTRING(1 2,3 4)", None)
+            .unwrap()
+            .with_srid(4326);
+        assert_eq!(geom.get_srid(), Some(4326));
+        assert_eq!(geom.as_text(None).unwrap(), "LINESTRING(1 2,3 4)");
+        assert_eq!(geom.as_ewkt(None).unwrap(), "SRID=4326;LINESTRING(1 2,3 4)");
+    }
+
+    #[test]
+    fn test_dwithin() {
+        let polygon = LWGeom::from_text("POLYGON((0 0,10 0,10 10,0 10,0 0))", None).unwrap();
+        let point = LWGeom::from_text("POINT(20 5)", None).unwrap();
+
+        let distance = polygon.distance(&point).unwrap();
+        for dist in [0.0, 5.0, 9.0, 10.0, 11.0, 2000.0] {
+            assert_eq!(polygon.dwithin(&point, dist).unwrap(), distance <= dist);
+        }
+
+        assert!(matches!(
+            polygon.dwithin(&point, -1.0),
+            Err(LWGeomError::InvalidParameterError(_, _))
+        ));
+        let empty = LWGeom::from_text("POINT EMPTY", None).unwrap();
+        assert!(!point.dwithin(&empty, 10.0).unwrap());
+    }
+
+    #[test]
+    fn test_collect() {
+        let points = ["POINT(0 0)", "POINT(1 1)", "POINT(2 2)"]
+            .map(|wkt| LWGeom::from_text(wkt, Some(4326)).unwrap());
+        assert_eq!(
+            LWGeom::collect(&points).unwrap().as_ewkt(None).unwrap(),
+            "SRID=4326;MULTIPOINT(0 0,1 1,2 2)"
+        );
+
+        let mixed =
+            ["POINT(0 0)", "LINESTRING(0 0,1 1)"].map(|wkt| LWGeom::from_text(wkt, None).unwrap());
+        assert_eq!(
+            LWGeom::collect(&mixed).unwrap().as_text(None).unwrap(),
+            "GEOMETRYCOLLECTION(POINT(0 0),LINESTRING(0 0,1 1))"
+        );
+
+        let mismatched = [
+            LWGeom::from_text("POINT(0 0)", Some(4326)).unwrap(),
+            LWGeom::from_text("POINT(0 0)", Some(3857)).unwrap(),
+        ];
+        assert!(matches!(
+            LWGeom::collect(&mismatched),
+            Err(LWGeomError::SRIDMismatchError(_, 4326, 3857))
+        ));
+        assert!(LWGeom::collect(&[]).is_err());
+    }
+
+    #[test]
+    fn test_dfullywithin() {
+        let point = LWGeom::from_text("POINT(1 1)", None).unwrap();
+        let line = LWGeom::from_text("LINESTRING(0 0,4 0)", None).unwrap();
+        assert!(point.dwithin(&line, 1.0).unwrap());
+        assert!(point.dfullywithin(&line, 1.0).unwrap());
+        assert!(!point.dfullywithin(&line, 0.5).unwrap());
+        assert!(!line.dfullywithin(&point, 1.0).unwrap());
+        assert!(line.dfullywithin(&point, 3.5).unwrap());
+
+        // A short segment inside a large square is within any distance of it,
+        // but the square's far corners are not within 1 of the segment.
+        let segment = LWGeom::from_text("LINESTRING(1 1,2 1)", None).unwrap();
+        let square = LWGeom::from_text("POLYGON((0 0,10 0,10 10,0 10,0 0))", None).unwrap();
+        assert!(segment.dfullywithin(&square, 1.0).unwrap());
+        assert!(!square.dfullywithin(&segment, 1.0).unwrap());
+
+        assert!(matches!(
+            point.dfullywithin(&line, -1.0),
+            Err(LWGeomError::InvalidParameterError(_, _))
+        ));
+        let other = LWGeom::from_text("POINT(1 1)", Some(4326)).unwrap();
+        assert!(matches!(
+            point.dfullywithin(&other, 1.0),
+            Err(LWGeomError::SRIDMismatchError(_, 0, 4326))
+        ));
+    }
+
+    #[test]
+    fn test_unary_union() {
+        let squares = LWGeom::from_text(
+            "MULTIPOLYGON(((0 0,2 0,2 2,0 2,0 0)),((1 1,3 1,3 3,1 3,1 1)))",
+            None,
+        )
+        .unwrap();
+        let result = squares.unary_union().unwrap();
+        assert_eq!(result.describe().geom_type, "Polygon");
+        assert_eq!(result.num_geometries(), 1);
+        assert_eq!(result.area(), 7.0);
+    }
+
+    #[test]
+    fn test_angle() {
+        let point = |wkt| LWGeom::from_text(wkt, None).unwrap();
+        let (p1, p2, p3) = (
+            point("POINT(0 0)"),
+            point("POINT(10 10)"),
+            point("POINT(20 0)"),
+        );
+        let degrees = angle(&p1, &p2, &p3, None).unwrap().to_degrees();
+        assert!((degrees - 270.0).abs() < 1e-9);
+
+        let (p1, p2, p3, p4) = (
+            point("POINT(0 0)"),
+            point("POINT(0 1)"),
+            point("POINT(0 0)"),
+            point("POINT(1 0)"),
+        );
+        let degrees = angle(&p1, &p2, &p3, Some(&p4)).unwrap().to_degrees();
+        assert!((degrees - 90.0).abs() < 1e-9);
+
+        match angle(&p1, &p3, &p4, None) {
+            Err(LWGeomError::InvalidParameterError(_, pair)) => assert_eq!(pair, "p2, p1"),
+            _ => panic!("coincident points should be rejected"),
+        }
+        let line = point("LINESTRING(0 0,1 1)");
+        assert!(angle(&p1, &line, &p3, None).is_err());
+    }
+
+    #[test]
+    fn test_offset_curve() {
+        let line = LWGeom::from_text("LINESTRING(0 0,10 0)", None).unwrap();
+        let params = BufferParams::default();
+        assert_eq!(
+            line.offset_curve(1.0, &params)
+                .unwrap()
+                .as_text(None)
+                .unwrap(),
+            "LINESTRING(0 1,10 1)"
+        );
+
+        let right = line.offset_curve(-1.0, &params).unwrap();
+        let bbox = right.get_bbox_ref();
+        assert_eq!((bbox.xmin(), bbox.xmax()), (0.0, 10.0));
+        assert_eq!((bbox.ymin(), bbox.ymax()), (-1.0, -1.0));
+
+        let polygon = LWGeom::from_text("POLYGON((0 0,1 0,1 1,0 0))", None).unwrap();
+        assert!(matches!(
+            polygon.offset_curve(1.0, &params),
+            Err(LWGeomError::InvalidParameterError(_, _))
+        ));
+
+        let lines = LWGeom::from_text(
+            "GEOMETRYCOLLECTION(LINESTRING(0 0,10 0),MULTILINESTRING((0 5,10 5)))",
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            lines.offset_curve(1.0, &params).unwrap().count_vertices(),
+            4
+        );
+
+        let mixed = LWGeom::from_text(
+            "GEOMETRYCOLLECTION(LINESTRING(0 0,10 0),POLYGON((0 0,1 0,1 1,0 0)))",
+            None,
+        )
+        .unwrap();
+        assert!(matches!(
+            mixed.offset_curve(1.0, &params),
+            Err(LWGeomError::InvalidParameterError(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_clip_by_rect() {
+        let polygon = LWGeom::from_text("POLYGON((0 0,10 0,10 10,0 10,0 0))", None).unwrap();
+        let clipped = polygon.clip_by_rect(2.0, 2.0, 5.0, 5.0).unwrap();
+        assert_eq!(clipped.area(), 9.0);
+        let bbox = clipped.get_bbox_ref();
+        assert_eq!(
+            (bbox.xmin(), bbox.ymin(), bbox.xmax(), bbox.ymax()),
+            (2.0, 2.0, 5.0, 5.0)
+        );
+
+        assert!(
+            polygon
+                .clip_by_rect(20.0, 20.0, 30.0, 30.0)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_closest_point_on_self() {
+        let line = LWGeom::from_ewkt("SRID=4326;LINESTRING Z(0 0 5,10 0 5)").unwrap();
+        let point = LWGeom::from_ewkt("SRID=4326;POINT Z(5 3 1)").unwrap();
+        assert_eq!(
+            line.closest_point(&point).unwrap().as_ewkt(None).unwrap(),
+            "SRID=4326;POINT(5 0)"
+        );
+        assert_eq!(
+            point.closest_point(&line).unwrap().as_ewkt(None).unwrap(),
+            "SRID=4326;POINT(5 3)"
+        );
+
+        let other = LWGeom::from_text("POINT(5 3)", None).unwrap();
+        assert!(matches!(
+            line.closest_point(&other),
+            Err(LWGeomError::SRIDMismatchError(_, 4326, 0))
+        ));
+        let empty = LWGeom::from_ewkt("SRID=4326;POINT EMPTY").unwrap();
+        assert!(matches!(
+            line.closest_point(&empty),
+            Err(LWGeomError::EmptyGeometryError(_))
+        ));
+    }
+
+    #[test]
+    fn test_box2d_dimensions() {
+        let geom = LWGeom::from_text("LINESTRING(1 2,4 8)", None).unwrap();
+        let bbox = geom.get_bbox_ref();
+        assert_eq!(bbox.width(), 3.0);
+        assert_eq!(bbox.height(), 6.0);
+        assert_eq!(bbox.area(), 18.0);
+    }
+
+    #[test]
+    fn test_shortest_line_intersecting() {
+        let line1 = LWGeom::from_ewkt("SRID=3857;LINESTRING(0 0,10 10)").unwrap();
+        let line2 = LWGeom::from_ewkt("SRID=3857;LINESTRING(0 10,10 0)").unwrap();
+        let result = line1.shortest_line(&line2).unwrap();
+        assert_eq!(result.get_srid(), Some(3857));
+        assert_eq!(result.length(), 0.0);
+        assert_eq!(result.count_vertices(), 2);
+        assert_eq!(
+            result.start_point().unwrap().as_text(None).unwrap(),
+            "POINT(5 5)"
+        );
+
+        let other = LWGeom::from_text("POINT(0 0)", None).unwrap();
+        assert!(matches!(
+            line1.shortest_line(&other),
+            Err(LWGeomError::SRIDMismatchError(_, 3857, 0))
+        ));
+        let empty = LWGeom::from_ewkt("SRID=3857;LINESTRING EMPTY").unwrap();
+        assert!(matches!(
+            line1.shortest_line(&empty),
+            Err(LWGeomError::EmptyGeometryError(_))
+        ));
+    }
+
+    #[test]
+    fn test_box2d_contains_point() {
+        let geom = LWGeom::from_text("LINESTRING(0 0,10 5)", None).unwrap();
+        let bbox = geom.get_bbox_ref();
+        assert!(bbox.contains_point(3.0, 2.0));
+        assert!(bbox.contains_point(10.0, 5.0));
+        assert!(bbox.contains_point(0.0, 3.0));
+        assert!(!bbox.contains_point(10.5, 2.0));
+        assert!(!bbox.contains_point(3.0, -0.1));
+    }
+
+    #[test]
+    fn test_longest_line() {
+        let rectangle = LWGeom::from_text("POLYGON((0 0,3 0,3 4,0 4,0 0))", None).unwrap();
+        let diameter = rectangle.longest_line(&rectangle).unwrap();
+        assert_eq!(diameter.length(), 5.0);
+        for end in [diameter.start_point(), diameter.end_point()] {
+            assert_eq!(rectangle.distance(&end.unwrap()).unwrap(), 0.0);
+        }
+
+        let point = LWGeom::from_text("POINT(0 -2)", None).unwrap();
+        let line = point.longest_line(&rectangle).unwrap();
+        assert_eq!(line.as_text(None).unwrap(), "LINESTRING(0 -2,3 4)");
+        assert_eq!(line.length(), point.max_distance(&rectangle).unwrap());
+
+        let empty = LWGeom::from_text("POLYGON EMPTY", None).unwrap();
+        assert!(matches!(
+            rectangle.longest_line(&empty),
+            Err(LWGeomError::EmptyGeometryError(_))
+        ));
+    }
+
+    #[test]
+    fn test_box2d_intersects() {
+        let bbox = |wkt| LWGeom::from_text(wkt, None).unwrap();
+        let (a, overlapping, touching, disjoint) = (
+            bbox("LINESTRING(0 0,2 2)"),
+            bbox("LINESTRING(1 1,3 3)"),
+            bbox("LINESTRING(2 0,4 2)"),
+            bbox("LINESTRING(5 5,6 6)"),
+        );
+        assert!(a.get_bbox_ref().intersects(overlapping.get_bbox_ref()));
+        assert!(a.get_bbox_ref().intersects(touching.get_bbox_ref()));
+        assert!(!a.get_bbox_ref().intersects(disjoint.get_bbox_ref()));
+    }
+
+    #[test]
+    fn test_area_spheroid() {
+        let wgs84 = Spheroid::wgs84();
+        let octant = LWGeom::from_text("POLYGON((0 0,90 0,0 90,0 0))", Some(4326)).unwrap();
+        let expected = 510_065_621_724_088.5 / 8.0;
+        let area = octant.area_spheroid(&wgs84).unwrap();
+        assert!((area - expected).abs() / expected < 1e-6);
+
+        assert!((wgs84.authalic_radius() - 6_371_007.181).abs() < 1e-3);
+        let area = octant.area_sphere(&wgs84).unwrap();
+        assert!((area - expected).abs() / expected < 1e-6);
+
+        // Wyoming is bounded by 41°N, 45°N, 104.05°W and 111.05°W. Its edges
+        // follow parallels, so they are densified before measuring.
+        let parallel = |lat: f64, from: f64, to: f64| {
+            (0..=700).map(move |i| format!("{} {lat}", from + (to - from) * i as f64 / 700.0))
+        };
+        let ring: Vec<String> = parallel(41.0, -111.05, -104.05)
+            .chain(parallel(45.0, -104.05, -111.05))
+            .chain(core::iter::once("-111.05 41".to_owned()))
+            .collect();
+        let wyoming =
+            LWGeom::from_text(&format!("POLYGON(({}))", ring.join(",")), Some(4326)).unwrap();
+        // Closed-form ellipsoidal area of that graticule cell.
+        let expected = 253_588_376_329.0;
+        let area = wyoming.area_spheroid(&wgs84).unwrap();
+        assert!((area - expected).abs() / expected < 1e-3);
+        let area = wyoming.area_sphere(&wgs84).unwrap();
+        assert!((area - expected).abs() / expected < 5e-3);
+
+        let square = LWGeom::from_text("POLYGON((-10 -10,10 -10,10 10,-10 10,-10 -10))", None)
+            .unwrap()
+            .area_spheroid(&wgs84)
+            .unwrap();
+        let antimeridian =
+            LWGeom::from_text("POLYGON((170 -10,-170 -10,-170 10,170 10,170 -10))", None)
+                .unwrap()
+                .area_spheroid(&wgs84)
+                .unwrap();
+        assert!(square > 0.0);
+        assert!((antimeridian - square).abs() / square < 1e-9);
+
+        let projected = LWGeom::from_text("POLYGON((0 0,1000 0,0 1000,0 0))", None).unwrap();
+        assert!(matches!(
+            projected.area_spheroid(&wgs84),
+            Err(LWGeomError::CoordinateOutOfRangeError(_, value)) if value == 1000.0
+        ));
+    }
+
+    #[test]
+    fn test_box2d_to_lwgeom() {
+        let geom = LWGeom::from_text("LINESTRING(1 2,4 8,-2 5)", None).unwrap();
+        let bbox = geom.get_bbox_ref();
+        let polygon = bbox.to_lwgeom(4326);
+        assert_eq!(polygon.get_srid(), Some(4326));
+        assert_eq!(polygon.area(), bbox.area());
+
+        let polygon_bbox = polygon.get_bbox_ref();
+        assert_eq!(
+            (
+                polygon_bbox.xmin(),
+                polygon_bbox.ymin(),
+                polygon_bbox.xmax(),
+                polygon_bbox.ymax()
+            ),
+            (bbox.xmin(), bbox.ymin(), bbox.xmax(), bbox.ymax())
+        );
+    }
+
+    #[test]
+    fn test_distance_spheroid() {
+        let wgs84 = Spheroid::wgs84();
+        let geom = |wkt| LWGeom::from_text(wkt, Some(4326)).unwrap();
+        let degree = wgs84.a() * core::f64::consts::PI / 180.0;
+
+        let distance = geom("POINT(0 0)")
+            .distance_spheroid(&geom("POINT(1 0)"), &wgs84)
+            .unwrap();
+        assert!((distance - degree).abs() < 1e-3);
+
+        let polygon = geom("POLYGON((-1 -1,1 -1,1 1,-1 1,-1 -1))");
+        assert_eq!(
+            geom("POINT(0.5 0.5)")
+                .distance_spheroid(&polygon, &wgs84)
+                .unwrap(),
+            0.0
+        );
+
+        let distance = geom("LINESTRING(0 -1,0 1)")
+            .distance_spheroid(&geom("LINESTRING(1 -1,1 1)"), &wgs84)
+            .unwrap();
+        assert!((distance - degree).abs() < 1e-3);
+
+        let (a, b) = (geom("POINT(0 0)"), geom("POINT(1 0)"));
+        assert!(a.dwithin_spheroid(&b, degree + 1.0, &wgs84).unwrap());
+        assert!(!a.dwithin_spheroid(&b, degree - 1.0, &wgs84).unwrap());
+
+        assert!(matches!(
+            a.distance_spheroid(&geom("POINT(0 95)"), &wgs84),
+            Err(LWGeomError::CoordinateOutOfRangeError(_, value)) if value == 95.0
+        ));
+        assert!(matches!(
+            a.distance_spheroid(&LWGeom::from_text("POINT(1 0)", None).unwrap(), &wgs84),
+            Err(LWGeomError::SRIDMismatchError(_, 4326, 0))
+        ));
+    }
+
+    #[test]
+    fn test_box2d_expand() {
+        let geom = LWGeom::from_text("LINESTRING(1 2,4 8)", None).unwrap();
+        let bbox = geom.get_bbox_ref();
+        let expanded = bbox.expand(1.0);
+        assert_eq!(expanded.width(), bbox.width() + 2.0);
+        assert_eq!(expanded.height(), bbox.height() + 2.0);
+        assert_eq!((expanded.xmin(), expanded.ymin()), (0.0, 1.0));
+        assert_eq!(bbox.to_string(), "GBOX((1,2),(4,8))");
+    }
+
+    #[test]
+    fn test_length_spheroid() {
+        let wgs84 = Spheroid::wgs84();
+        let geom = |wkt| LWGeom::from_text(wkt, Some(4326)).unwrap();
+        let degree = wgs84.a() * core::f64::consts::PI / 180.0;
+
+        let length = geom("LINESTRING(179 0,-179 0)")
+            .length_spheroid(&wgs84)
+            .unwrap();
+        assert!((length - 2.0 * degree).abs() < 1e-3);
+
+        let lines = geom("MULTILINESTRING((0 0,1 0),(179 0,-179 0))")
+            .length_spheroid(&wgs84)
+            .unwrap();
+        assert!((lines - 3.0 * degree).abs() < 1e-3);
+
+        for wkt in ["LINESTRING(0 89,0 90)", "LINESTRING(0 89,180 89)"] {
+            let length = geom(wkt).length_spheroid(&wgs84).unwrap();
+            assert!(length.is_finite() && length > 0.0);
+        }
+
+        let square = geom("POLYGON((0 0,1 0,1 1,0 1,0 0))");
+        let perimeter = square.length_spheroid(&wgs84).unwrap();
+        assert!(perimeter > 3.9 * 110_000.0 && perimeter < 4.0 * degree);
+    }
+
+    #[test]
+    fn test_project() {
+        use core::f64::consts::{FRAC_PI_2, PI};
+
+        let wgs84 = Spheroid::wgs84();
+        let degree = wgs84.a() * PI / 180.0;
+        let coords = |geom: &LWGeom| {
+            let point = geom.as_point().unwrap();
+            (point.x().unwrap(), point.y().unwrap())
+        };
+
+        let origin = LWGeom::from_text("POINT(0 0)", Some(4326)).unwrap();
+        let (x, y) = coords(&origin.project(degree, FRAC_PI_2, &wgs84).unwrap());
+        assert!((x - 1.0).abs() < 1e-9 && y.abs() < 1e-9);
+        let (x, y) = coords(&origin.project(degree, 5.0 * FRAC_PI_2, &wgs84).unwrap());
+        assert!((x - 1.0).abs() < 1e-9 && y.abs() < 1e-9);
+        let (x, y) = coords(&origin.project(-degree, FRAC_PI_2, &wgs84).unwrap());
+        assert!((x + 1.0).abs() < 1e-9 && y.abs() < 1e-9);
+
+        let east = LWGeom::from_text("POINT(179.5 0)", Some(4326)).unwrap();
+        let projected = east.project(degree, FRAC_PI_2, &wgs84).unwrap();
+        assert_eq!(projected.get_srid(), Some(4326));
+        let (x, _) = coords(&projected);
+        assert!((x + 179.5).abs() < 1e-9);
+
+        let north = LWGeom::from_text("POINT(0 89)", Some(4326)).unwrap();
+        let (x, y) = coords(&north.project(2.0 * 111_000.0, 0.0, &wgs84).unwrap());
+        assert!((-180.0..=180.0).contains(&x));
+        assert!(y.is_finite() && y < 90.0);
+
+        let line = LWGeom::from_text("LINESTRING(0 0,1 1)", Some(4326)).unwrap();
+        assert!(line.project(degree, 0.0, &wgs84).is_err());
+    }
+
+    #[test]
+    fn test_intersects() {
+        let polygon = |wkt| LWGeom::from_text(wkt, None).unwrap();
+        let a = polygon("POLYGON((0 0,2 0,2 2,0 2,0 0))");
+        let b = polygon("POLYGON((1 1,3 1,3 3,1 3,1 1))");
+        let c = polygon("POLYGON((5 5,6 5,6 6,5 6,5 5))");
+        assert!(a.intersects(&b).unwrap());
+        assert!(!a.intersects(&c).unwrap());
+    }
+
+    #[test]
+    fn test_contains() {
+        let polygon = LWGeom::from_text("POLYGON((0 0,10 0,10 10,0 10,0 0))", None).unwrap();
+        let interior = LWGeom::from_text("POINT(5 5)", None).unwrap();
+        let boundary = LWGeom::from_text("POINT(10 5)", None).unwrap();
+        assert!(polygon.contains(&interior).unwrap());
+        assert!(!polygon.contains(&boundary).unwrap());
+        assert!(!interior.contains(&polygon).unwrap());
+    }
+
+    #[test]
+    fn test_simplify() {
+        let line = LWGeom::from_ewkt("SRID=4326;LINESTRING Z(0 0 1,5 0.1 2,10 0 3)").unwrap();
+        let result = line.simplify(1.0, false).unwrap();
+        assert_eq!(
+            result.as_ewkt(None).unwrap(),
+            "SRID=4326;LINESTRING(0 0 1,10 0 3)"
+        );
+
+        let polygon = LWGeom::from_text(
+            "POLYGON((0 0,10 0,10 10,0 10,0 0),(5 5,5.1 5,5.1 5.1,5 5.1,5 5))",
+            None,
+        )
+        .unwrap();
+        let result = polygon.simplify(1.0, false).unwrap();
+        assert_eq!(result.as_polygon().unwrap().num_rings(), 1);
+
+        let tiny = LWGeom::from_text("POLYGON((0 0,0.1 0,0.1 0.1,0 0.1,0 0))", None).unwrap();
+        assert!(tiny.simplify(1.0, false).unwrap().is_empty());
+        assert!(!tiny.simplify(1.0, true).unwrap().is_empty());
+
+        let collection = LWGeom::from_text(
+            "GEOMETRYCOLLECTION(LINESTRING(0 0,5 0.1,10 0),POINT(1 1))",
+            None,
+        )
+        .unwrap();
+        assert_eq!(collection.simplify(1.0, false).unwrap().count_vertices(), 3);
+        assert!(line.simplify(-1.0, false).is_err());
+    }
+
+    #[test]
+    fn test_chaikin_smooth() {
+        let line = LWGeom::from_text("LINESTRING(0 0,8 8,16 0)", None).unwrap();
+        let smoothed = line.chaikin_smooth(2, true).unwrap();
+        assert!(smoothed.count_vertices() > line.count_vertices());
+        assert_eq!(
+            smoothed.start_point().unwrap().as_text(None).unwrap(),
+            "POINT(0 0)"
+        );
+        assert_eq!(
+            smoothed.end_point().unwrap().as_text(None).unwrap(),
+            "POINT(16 0)"
+        );
+
+        let moved = line.chaikin_smooth(1, false).unwrap();
+        assert_ne!(
+            moved.start_point().unwrap().as_text(None).unwrap(),
+            "POINT(0 0)"
+        );
+
+        let polygon = LWGeom::from_text("POLYGON((0 0,8 0,8 8,0 8,0 0))", None).unwrap();
+        let smoothed = polygon.chaikin_smooth(3, false).unwrap();
+        assert!(smoothed.count_vertices() > polygon.count_vertices());
+        assert!(smoothed.is_closed());
+
+        assert!(line.chaikin_smooth(6, true).is_err());
+        assert!(line.chaikin_smooth(0, true).is_err());
+    }
+
+    #[test]
+    fn test_within() {
+        let polygon = LWGeom::from_text("POLYGON((0 0,10 0,10 10,0 10,0 0))", None).unwrap();
+        for wkt in ["POINT(5 5)", "POINT(10 5)", "POINT(20 5)"] {
+            let point = LWGeom::from_text(wkt, None).unwrap();
+            assert_eq!(
+                point.within(&polygon).unwrap(),
+                polygon.contains(&point).unwrap()
+            );
+        }
+        let point = LWGeom::from_text("POINT(5 5)", None).unwrap();
+        assert!(point.within(&polygon).unwrap());
+        assert!(!polygon.within(&point).unwrap());
+    }
+
+    #[test]
+    fn test_covers_covered_by() {
+        let polygon = LWGeom::from_text("POLYGON((0 0,10 0,10 10,0 10,0 0))", None).unwrap();
+        let boundary = LWGeom::from_text("POINT(10 5)", None).unwrap();
+        assert!(polygon.covers(&boundary).unwrap());
+        assert!(boundary.covered_by(&polygon).unwrap());
+        assert!(!polygon.contains(&boundary).unwrap());
+        assert!(!boundary.within(&polygon).unwrap());
+
+        let outside = LWGeom::from_text("POINT(20 5)", None).unwrap();
+        assert!(!polygon.covers(&outside).unwrap());
+        assert!(!outside.covered_by(&polygon).unwrap());
+    }
+
+    #[test]
+    fn test_simplify_vw() {
+        let line = LWGeom::from_text("LINESTRING(0 0,1 0.1,2 0,5 5,10 0)", None).unwrap();
+        let result = line.simplify_vw(1.0, false).unwrap();
+        assert_eq!(result.as_text(None).unwrap(), "LINESTRING(0 0,5 5,10 0)");
+
+        let areas = line.set_effective_area(0.0, true).unwrap();
+        assert!(areas.has_m());
+        assert_eq!(areas.count_vertices(), 5);
+        let second = areas.point_n(2).unwrap();
+        let m = second.as_point().unwrap().m().unwrap();
+        assert!((m - 0.1).abs() < 1e-9);
+        assert_eq!(
+            line.set_effective_area(1.0, true).unwrap().count_vertices(),
+            3
+        );
+
+        let square = LWGeom::from_text("POLYGON((0 0,1 0,1 1,0 1,0 0))", None).unwrap();
+        let kept = square.simplify_vw(100.0, true).unwrap();
+        assert_eq!(kept.count_vertices(), 4);
+        let collapsed = square.simplify_vw(100.0, false).unwrap();
+        assert!(collapsed.is_empty());
+        assert_eq!(collapsed.describe().geom_type, "Polygon");
+
+        let multi = LWGeom::from_text(
+            "MULTIPOLYGON(((0 0,0 100,100 100,100 0,0 0)),((200 0,201 0,201 1,200 1,200 0)))",
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            multi
+                .simplify_vw(100.0, false)
+                .unwrap()
+                .as_text(None)
+                .unwrap(),
+            "MULTIPOLYGON(((0 0,0 100,100 100,100 0,0 0)))"
+        );
+        assert_eq!(multi.simplify_vw(100.0, true).unwrap().num_geometries(), 2);
+        assert!(line.simplify_vw(-1.0, true).is_err());
+    }
+
+    #[test]
+    fn test_segmentize() {
+        let line = LWGeom::from_ewkt("SRID=3857;LINESTRING ZM(0 0 0 0,10 0 10 20)").unwrap();
+        let result = line.segmentize(5.0).unwrap();
+        assert_eq!(result.get_srid(), Some(3857));
+        assert_eq!(
+            result.as_text(None).unwrap(),
+            "LINESTRING ZM (0 0 0 0,5 0 5 10,10 0 10 20)"
+        );
+
+        let polygon = LWGeom::from_text("POLYGON((0 0,4 0,4 4,0 4,0 0))", None).unwrap();
+        assert_eq!(polygon.segmentize(1.0).unwrap().count_vertices(), 17);
+
+        assert!(line.segmentize(0.0).is_err());
+        assert!(line.segmentize(-1.0).is_err());
+        assert!(line.segmentize(1e-9).is_err());
+    }
+
+    #[test]
+    fn test_touches() {
+        let polygon = |wkt| LWGeom::from_text(wkt, None).unwrap();
+        let a = polygon("POLYGON((0 0,2 0,2 2,0 2,0 0))");
+        let neighbour = polygon("POLYGON((2 0,4 0,4 2,2 2,2 0))");
+        let overlapping = polygon("POLYGON((1 1,3 1,3 3,1 3,1 1))");
+        assert!(a.touches(&neighbour).unwrap());
+        assert!(!a.touches(&overlapping).unwrap());
+    }
+
+    #[test]
+    fn test_crosses() {
+        let polygon = LWGeom::from_text("POLYGON((0 0,10 0,10 10,0 10,0 0))", None).unwrap();
+        let crossing = LWGeom::from_text("LINESTRING(5 5,15 5)", None).unwrap();
+        let inside = LWGeom::from_text("LINESTRING(2 2,8 8)", None).unwrap();
+        assert!(crossing.crosses(&polygon).unwrap());
+        assert!(!inside.crosses(&polygon).unwrap());
+    }
+
+    #[test]
+    fn test_segmentize_sphere() {
+        let midpoint = |wkt| {
+            let result = LWGeom::from_text(wkt, Some(4326))
+                .unwrap()
+                .segmentize_sphere(10f64.to_radians())
+                .unwrap();
+            assert_eq!(result.count_vertices(), 3);
+            let point = result.point_n(2).unwrap();
+            let point = point.as_point().unwrap();
+            (point.x().unwrap(), point.y().unwrap())
+        };
+
+        let (x, y) = midpoint("LINESTRING(-10 45,10 45)");
+        assert!(x.abs() < 1e-9);
+        assert!((y - 45.438548586742).abs() < 1e-9);
+
+        let (x, y) = midpoint("LINESTRING(170 45,-170 45)");
+        assert!((x.abs() - 180.0).abs() < 1e-9);
+        assert!((y - 45.438548586742).abs() < 1e-9);
+
+        let line = LWGeom::from_text("LINESTRING(0 0,1 0)", Some(4326)).unwrap();
+        assert!(line.segmentize_sphere(0.0).is_err());
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let polygon = |wkt| LWGeom::from_text(wkt, None).unwrap();
+        let a = polygon("POLYGON((0 0,2 0,2 2,0 2,0 0))");
+        let partial = polygon("POLYGON((1 1,3 1,3 3,1 3,1 1))");
+        let contained = polygon("POLYGON((0.5 0.5,1.5 0.5,1.5 1.5,0.5 1.5,0.5 0.5))");
+        assert!(a.overlaps(&partial).unwrap());
+        assert!(!a.overlaps(&contained).unwrap());
+    }
+
+    #[test]
+    fn test_remove_repeated_points() {
+        let line = LWGeom::from_text("LINESTRING(0 0,0 0,1 0,1.05 0,2 0)", None).unwrap();
+        assert_eq!(
+            line.remove_repeated_points(0.0)
+                .unwrap()
+                .as_text(None)
+                .unwrap(),
+            "LINESTRING(0 0,1 0,1.05 0,2 0)"
+        );
+        assert_eq!(
+            line.remove_repeated_points(0.1)
+                .unwrap()
+                .as_text(None)
+                .unwrap(),
+            "LINESTRING(0 0,1 0,2 0)"
+        );
+
+        let mut short = LWGeom::from_text("LINESTRING(0 0,0.01 0,0.02 0)", None).unwrap();
+        short.remove_repeated_points_in_place(1.0).unwrap();
+        assert_eq!(short.count_vertices(), 2);
+
+        let ring = LWGeom::from_text("POLYGON((0 0,0.1 0,0.1 0.1,0 0.1,0 0))", None).unwrap();
+        let result = ring.remove_repeated_points(1.0).unwrap();
+        assert_eq!(result.count_vertices(), 4);
+        assert!(result.is_closed());
+
+        assert!(line.remove_repeated_points(-1.0).is_err());
+    }
+
+    #[test]
+    fn test_disjoint() {
+        let geom = |wkt| LWGeom::from_text(wkt, None).unwrap();
+        let (a, b) = (geom("POINT(0 0)"), geom("POINT(1 1)"));
+        assert!(a.disjoint(&b).unwrap());
+        assert!(!a.intersects(&b).unwrap());
+
+        let square = geom("POLYGON((0 0,1 0,1 1,0 1,0 0))");
+        let neighbour = geom("POLYGON((1 0,2 0,2 1,1 1,1 0))");
+        assert!(!square.disjoint(&neighbour).unwrap());
+    }
+
+    #[test]
+    fn test_snap_to_grid() {
+        let line = LWGeom::from_text("LINESTRING(1.1 2.6,3.4 4.2,3.45 4.1)", None).unwrap();
+        assert_eq!(
+            line.snap_to_grid(0.0, 0.0, 1.0, 1.0)
+                .unwrap()
+                .as_text(None)
+                .unwrap(),
+            "LINESTRING(1 3,3 4)"
+        );
+        assert_eq!(
+            line.snap_to_grid(0.0, 0.0, 1.0, 0.0)
+                .unwrap()
+                .as_text(None)
+                .unwrap(),
+            "LINESTRING(1 2.6,3 4.2,3 4.1)"
+        );
+
+        let point = LWGeom::from_text("POINT Z(1.1 2.6 3.7)", None).unwrap();
+        let grid = GridSpec {
+            size_x: 1.0,
+            size_y: 1.0,
+            size_z: 0.5,
+            ..Default::default()
+        };
+        assert_eq!(
+            point
+                .snap_to_grid_spec(&grid)
+                .unwrap()
+                .as_text(None)
+                .unwrap(),
+            "POINT Z (1 3 3.5)"
+        );
+
+        for wkt in [
+            "POLYGON((0 0,0.1 0,0.1 0.1,0 0))",
+            "LINESTRING(0 0,0.2 0.2)",
+        ] {
+            let collapsed = LWGeom::from_text(wkt, None)
+                .unwrap()
+                .snap_to_grid(0.0, 0.0, 1.0, 1.0)
+                .unwrap();
+            assert!(collapsed.is_empty());
+        }
+        assert!(line.snap_to_grid(0.0, 0.0, -1.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_reverse() {
+        let line = LWGeom::from_text("LINESTRING(0 0,1 1,2 0)", None).unwrap();
+        let reversed = line.reverse();
+        assert_eq!(reversed.as_text(None).unwrap(), "LINESTRING(2 0,1 1,0 0)");
+        assert!(reversed != line);
+        assert!(reversed.reverse() == line);
+
+        let mut collection = LWGeom::from_text(
+            "GEOMETRYCOLLECTION(POLYGON((0 0,1 0,1 1,0 0)),LINESTRING(0 0,1 1))",
+            None,
+        )
+        .unwrap();
+        collection.reverse_in_place();
+        assert_eq!(
+            collection.as_text(None).unwrap(),
+            "GEOMETRYCOLLECTION(POLYGON((0 0,1 1,1 0,0 0)),LINESTRING(1 1,0 0))"
+        );
+    }
+
+    #[test]
+    fn test_equals() {
+        let polygon = LWGeom::from_text("POLYGON((0 0,1 0,1 1,0 1,0 0))", None).unwrap();
+        let reversed = polygon.reverse();
+        assert!(polygon != reversed);
+        assert!(polygon.equals(&reversed).unwrap());
+
+        let shifted = LWGeom::from_text("POLYGON((1 1,0 1,0 0,1 0,1 1))", None).unwrap();
+        assert!(polygon.equals(&shifted).unwrap());
+
+        let other = LWGeom::from_text("POLYGON((0 0,2 0,2 2,0 2,0 0))", None).unwrap();
+        assert!(!polygon.equals(&other).unwrap());
+    }
+
+    #[test]
+    fn test_dwithin_threshold() {
+        let a = LWGeom::from_text("POINT(0 0)", None).unwrap();
+        let b = LWGeom::from_text("POINT(3 4)", None).unwrap();
+        assert!(a.dwithin(&b, 5.0).unwrap());
+        assert!(a.dwithin(&b, 5.001).unwrap());
+        assert!(!a.dwithin(&b, 4.999).unwrap());
+    }
+
+    #[test]
+    fn test_force_2d() {
+        let geom = LWGeom::from_ewkt("SRID=4326;LINESTRING ZM(1 2 3 4,5 6 7 8)").unwrap();
+        let result = geom.force_2d();
+        assert!(!result.has_z() && !result.has_m());
+        assert_eq!(
+            result.as_ewkt(None).unwrap(),
+            "SRID=4326;LINESTRING(1 2,5 6)"
+        );
+
+        let empty = LWGeom::from_text("POLYGON ZM EMPTY", None).unwrap();
+        assert_eq!(empty.force_2d().as_text(None).unwrap(), "POLYGON EMPTY");
+    }
+
+    #[test]
+    fn test_relate() {
+        let a = LWGeom::from_text("POLYGON((0 0,2 0,2 2,0 2,0 0))", None).unwrap();
+        let b = LWGeom::from_text("POLYGON((1 1,3 1,3 3,1 3,1 1))", None).unwrap();
+        assert_eq!(a.relate(&b).unwrap(), "212101212");
+
+        let point = LWGeom::from_text("POINT(1 1)", None).unwrap();
+        assert_eq!(a.relate(&point).unwrap(), "0F2FF1FF2");
+    }
+
+    #[test]
+    fn test_force_3d_4d() {
+        let parcel = LWGeom::from_ewkt("SRID=2193;LINESTRING(1 2,3 4)").unwrap();
+        let result = parcel.force_3dz(10.0);
+        assert!(result.has_z() && !result.has_m());
+        assert_eq!(
+            result.as_ewkt(None).unwrap(),
+            "SRID=2193;LINESTRING(1 2 10,3 4 10)"
+        );
+        assert_eq!(
+            result.as_text(None).unwrap(),
+            "LINESTRING Z (1 2 10,3 4 10)"
+        );
+
+        let result = parcel.force_3dm(5.0);
+        assert!(!result.has_z() && result.has_m());
+        assert_eq!(
+            result.as_ewkt(None).unwrap(),
+            "SRID=2193;LINESTRINGM(1 2 5,3 4 5)"
+        );
+
+        let point = LWGeom::from_text("POINT Z(1 2 3)", None).unwrap();
+        let result = point.force_4d(0.0, 7.0);
+        assert!(result.has_z() && result.has_m());
+        assert_eq!(result.as_text(None).unwrap(), "POINT ZM (1 2 3 7)");
+    }
+
+    #[test]
+    fn test_force_collection_force_multi() {
+        let geom = |wkt| LWGeom::from_ewkt(wkt).unwrap();
+
+        let point = geom("SRID=4326;POINT(1 2)");
+        assert_eq!(
+            point.force_collection().as_ewkt(None).unwrap(),
+            "SRID=4326;GEOMETRYCOLLECTION(POINT(1 2))"
+        );
+        assert_eq!(
+            geom("MULTIPOINT(1 2,3 4)")
+                .force_collection()
+                .as_text(None)
+                .unwrap(),
+            "GEOMETRYCOLLECTION(POINT(1 2),POINT(3 4))"
+        );
+        assert_eq!(
+            geom("POINT EMPTY")
+                .force_collection()
+                .as_text(None)
+                .unwrap(),
+            "GEOMETRYCOLLECTION EMPTY"
+        );
+
+        let multi = point.force_multi();
+        drop(point);
+        assert_eq!(multi.as_ewkt(None).unwrap(), "SRID=4326;MULTIPOINT(1 2)");
+        for (wkt, expected) in [
+            ("LINESTRING(0 0,1 1)", "MULTILINESTRING((0 0,1 1))"),
+            (
+                "POLYGON((0 0,1 0,1 1,0 0))",
+                "MULTIPOLYGON(((0 0,1 0,1 1,0 0)))",
+            ),
+            ("MULTIPOINT(1 2)", "MULTIPOINT(1 2)"),
+            ("POLYGON EMPTY", "MULTIPOLYGON EMPTY"),
+        ] {
+            assert_eq!(geom(wkt).force_multi().as_text(None).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_relate_pattern() {
+        let polygon = LWGeom::from_text("POLYGON((0 0,10 0,10 10,0 10,0 0))", None).unwrap();
+        let inner = LWGeom::from_text("POLYGON((2 2,4 2,4 4,2 4,2 2))", None).unwrap();
+        let contains = "T*****FF*";
+        assert!(polygon.relate_pattern(&inner, contains).unwrap());
+        assert!(!inner.relate_pattern(&polygon, contains).unwrap());
+        assert!(inner.relate_pattern(&polygon, "T*F**F***").unwrap());
+
+        assert!(matches!(
+            polygon.relate_pattern(&inner, "T*F"),
+            Err(LWGeomError::InvalidParameterError(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_lib_error() {
+        match LWGeom::from_ewkb(&[0x01, 0x63, 0x00, 0x00, 0x00]) {
+            Err(LWGeomError::LibError(message)) => assert!(message.contains("WKB")),
+            other => {
+                panic!(
+                    "expected LibError, got {:?}",
+                    other.map(|g| g.as_text(None))
+                )
+            }
+        }
+        assert!(LWGeom::from_text("POINT(1 2)", None).is_ok());
+
+        let point = LWGeom::from_text("POINT(1 2)", None).unwrap();
+        assert!(matches!(
+            point.node(),
+            Err(LWGeomError::LibError(message)) if message.contains("dimension")
+        ));
+    }
+
+    #[test]
+    fn test_force_sfs() {
+        let curve = LWGeom::from_text(
+            "CURVEPOLYGON(CIRCULARSTRING(0 0,4 0,4 4,0 4,0 0))",
+            Some(4326),
+        )
+        .unwrap();
+        let geom = curve.force_sfs(SfsVersion::V1_1).unwrap();
+        assert_eq!(geom.describe().geom_type, "Polygon");
+        assert_eq!(geom.get_srid(), Some(4326));
+        assert!(geom.describe().num_vertices > 5);
+
+        let triangle = LWGeom::from_text("TRIANGLE((0 0,0 1,1 1,0 0))", None).unwrap();
+        let sfs_11 = triangle.force_sfs(SfsVersion::V1_1).unwrap();
+        assert_eq!(sfs_11.describe().geom_type, "Polygon");
+        let sfs_12 = triangle.force_sfs(SfsVersion::V1_2).unwrap();
+        assert_eq!(sfs_12.describe().geom_type, "Triangle");
+    }
+
+    #[test]
+    fn test_force_cw() {
+        let geom = LWGeom::from_text(
+            "MULTIPOLYGON(((0 0,0 10,10 10,10 0,0 0),(2 2,2 4,4 4,4 2,2 2)),((20 0,30 0,30 10,20 10,20 0)))",
+            None,
+        )
+        .unwrap();
+        assert!(!geom.is_polygon_cw());
+        assert!(!geom.is_polygon_ccw());
+
+        let cw = geom.force_cw();
+        assert!(cw.is_polygon_cw());
+        assert!(!cw.is_polygon_ccw());
+        assert_eq!(
+            cw.as_text(None).unwrap(),
+            "MULTIPOLYGON(((0 0,0 10,10 10,10 0,0 0),(2 2,4 2,4 4,2 4,2 2)),((20 0,20 10,30 10,30 0,20 0)))"
+        );
+
+        let ccw = geom.force_ccw();
+        assert!(ccw.is_polygon_ccw());
+        assert!(!ccw.is_polygon_cw());
+        assert!(ccw.force_cw() == cw);
+    }
+
+    #[test]
+    fn test_last_error_per_thread() {
+        let barrier = std::sync::Arc::new(std::sync::Barrier::new(2));
+        let handles: Vec<_> = [0x62u8, 0x63]
+            .into_iter()
+            .map(|wkb_type| {
+                let barrier = barrier.clone();
+                std::thread::spawn(move || {
+                    assert!(LWGeom::from_ewkb(&[0x01, wkb_type, 0x00, 0x00, 0x00]).is_err());
+                    barrier.wait();
+                    // A later successful call does not erase the diagnostic.
+                    LWGeom::from_text("POINT(1 2)", None).unwrap();
+                    (wkb_type, last_error())
+                })
+            })
+            .collect();
+        for handle in handles {
+            let (wkb_type, message) = handle.join().unwrap();
+            assert!(message.unwrap().contains(&format!("({wkb_type})")));
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        let a = LWGeom::from_text("POLYGON((0 0,0 1,1 1,1 0,0 0))", Some(4326)).unwrap();
+        let b = LWGeom::from_text("POLYGON((1 1,0 1,0 0,1 0,1 1))", Some(4326)).unwrap();
+        assert!(a != b);
+        assert!(a.normalize().unwrap() == b.normalize().unwrap());
+
+        let mut c = b.clone();
+        c.normalize_in_place().unwrap();
+        assert!(c == a.normalize().unwrap());
+        assert_eq!(c.get_srid(), Some(4326));
+    }
+
+    #[test]
+    fn test_error_source() {
+        use std::error::Error;
+
+        let error = LWGeom::from_text("POINT(1\0 2)", None).err().unwrap();
+        assert!(matches!(error, LWGeomError::CStringError(_)));
+        assert!(error.source().unwrap().is::<std::ffi::NulError>());
+
+        let error = LWGeom::from_text("POINT(1 2", None).err().unwrap();
+        assert!(matches!(error, LWGeomError::WKTParseError(_)));
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn test_affine() {
+        let geom = LWGeom::from_text("LINESTRING(1 0,2 0)", None).unwrap();
+        let matrix = AffineMatrix::scaling(2.0, 2.0, 2.0)
+            .then(&AffineMatrix::rotation_z(std::f64::consts::FRAC_PI_2))
+            .then(&AffineMatrix::translation(10.0, 20.0, 30.0));
+        assert_eq!(
+            geom.affine(&matrix)
+                .snap_to_grid(0.0, 0.0, 1e-9, 1e-9)
+                .unwrap()
+                .as_text(None)
+                .unwrap(),
+            "LINESTRING(10 22,10 24)"
+        );
+
+        let mut geom = LWGeom::from_text("POINT Z(1 2 3)", None).unwrap();
+        geom.affine_in_place(&AffineMatrix::translation(1.0, 1.0, 1.0));
+        assert_eq!(geom.as_text(None).unwrap(), "POINT Z (2 3 4)");
+        geom.affine_in_place(&AffineMatrix::identity());
+        assert_eq!(geom.as_text(None).unwrap(), "POINT Z (2 3 4)");
+    }
+
+    #[test]
+    fn test_vertices() {
+        let geom = LWGeom::from_text("POLYGON((0 0,0 10,10 10,10 0,0 0),(2 2,4 2,4 4,2 2))", None)
+            .unwrap();
+        let vertices: Vec<[f64; 4]> = geom.vertices().collect();
+        assert_eq!(geom.vertices().len(), geom.count_vertices());
+        assert_eq!(vertices.len(), geom.count_vertices());
+        let xy: Vec<(f64, f64)> = vertices.iter().map(|v| (v[0], v[1])).collect();
+        assert_eq!(
+            xy[..5],
+            [
+                (0.0, 0.0),
+                (0.0, 10.0),
+                (10.0, 10.0),
+                (10.0, 0.0),
+                (0.0, 0.0)
+            ]
+        );
+        assert_eq!(xy[5..], [(2.0, 2.0), (4.0, 2.0), (4.0, 4.0), (2.0, 2.0)]);
+        assert!(vertices.iter().all(|v| v[2].is_nan() && v[3].is_nan()));
+
+        let line = LWGeom::from_text("LINESTRING M (1 2 3,4 5 6)", None).unwrap();
+        let vertex = line.vertices().last().unwrap();
+        assert_eq!((vertex[0], vertex[1], vertex[3]), (4.0, 5.0, 6.0));
+        assert!(vertex[2].is_nan());
+    }
+
+    #[test]
+    fn test_geometries() {
+        let multi = LWGeom::from_text(
+            "MULTIPOLYGON(((0 0,0 1,1 1,0 0)),((2 2,2 3,3 3,2 2)),((4 4,4 5,5 5,4 4)))",
+            None,
+        )
+        .unwrap();
+        let parts: Vec<&LWGeomRef> = multi.geometries().collect();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(
+            parts[2].as_text(None).unwrap(),
+            "POLYGON((4 4,4 5,5 5,4 4))"
+        );
+
+        let point = LWGeom::from_text("POINT(1 2)", None).unwrap();
+        let mut parts = point.geometries();
+        assert_eq!(parts.next().unwrap().as_text(None).unwrap(), "POINT(1 2)");
+        assert!(parts.next().is_none());
+    }
+
+    #[test]
+    fn test_translate_scale_rotate() {
+        let line = LWGeom::from_text("LINESTRING(1 2,3 6)", None).unwrap();
+        let moved = line.translate(10.0, -2.0);
+        assert_eq!(moved.as_text(None).unwrap(), "LINESTRING(11 0,13 4)");
+        assert_eq!(moved.get_bbox_ref().to_string(), "GBOX((11,0),(13,4))");
+
+        let scaled = line.scale(2.0, 0.5);
+        assert_eq!(scaled.as_text(None).unwrap(), "LINESTRING(2 1,6 3)");
+        assert_eq!(scaled.get_bbox_ref().to_string(), "GBOX((2,1),(6,3))");
+
+        let point = LWGeom::from_text("POINT Z(1 2 3)", None).unwrap();
+        assert_eq!(
+            point.translate_3d(1.0, 1.0, -3.0).as_text(None).unwrap(),
+            "POINT Z (2 3 0)"
+        );
+
+        let rotated = line.rotate(std::f64::consts::PI);
+        let bbox = rotated.get_bbox_ref();
+        assert!((bbox.xmin() + 3.0).abs() < 1e-6 && (bbox.xmax() + 1.0).abs() < 1e-6);
+        assert!((bbox.ymin() + 6.0).abs() < 1e-6 && (bbox.ymax() + 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rotate_around() {
+        use std::f64::consts::FRAC_PI_2;
+
+        let origin = LWGeom::from_text("POINT(5 5)", None).unwrap();
+        let fixed = origin.rotate_around_point(1.234, &origin).unwrap();
+        assert!(fixed.distance(&origin).unwrap() < 1e-9);
+
+        let line = LWGeom::from_text("LINESTRING(5 5,6 5)", None).unwrap();
+        let rotated = line
+            .rotate_around(FRAC_PI_2, 5.0, 5.0)
+            .snap_to_grid(0.0, 0.0, 1e-9, 1e-9)
+            .unwrap();
+        assert_eq!(rotated.as_text(None).unwrap(), "LINESTRING(5 5,5 6)");
+
+        let square = LWGeom::from_text("POLYGON((0 0,0 2,2 2,2 0,0 0))", None).unwrap();
+        let turned = square.rotate_around_centroid(FRAC_PI_2).unwrap();
+        assert!(
+            turned
+                .centroid()
+                .unwrap()
+                .distance(&square.centroid().unwrap())
+                .unwrap()
+                < 1e-9
+        );
+        let snapped = turned.snap_to_grid(0.0, 0.0, 1e-9, 1e-9).unwrap();
+        assert!(snapped.equals(&square).unwrap());
+
+        assert!(matches!(
+            line.rotate_around_point(1.0, &line),
+            Err(LWGeomError::InvalidParameterError(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_as_mvt_geom() {
+        let tile = LWGeom::from_text("POLYGON((0 0,0 10,10 10,10 0,0 0))", Some(3857)).unwrap();
+        let bounds = tile.get_bbox_ref();
+        let geom = LWGeom::from_text("POLYGON((5 5,5 15,15 15,15 5,5 5))", Some(3857)).unwrap();
+
+        let mvt = geom.as_mvt_geom(bounds, 4096, 0, true).unwrap().unwrap();
+        assert_eq!(mvt.get_srid(), Some(3857));
+        assert!(mvt.is_polygon_ccw());
+        let bbox = mvt.get_bbox_ref();
+        assert_eq!(
+            (bbox.xmin(), bbox.ymin(), bbox.xmax(), bbox.ymax()),
+            (2048.0, 0.0, 4096.0, 2048.0)
+        );
+        assert_eq!(mvt.area(), 2048.0 * 2048.0);
+
+        let unclipped = geom.as_mvt_geom(bounds, 4096, 0, false).unwrap().unwrap();
+        assert_eq!(unclipped.get_bbox_ref().xmax(), 6144.0);
+
+        let outside = LWGeom::from_text("POINT(50 50)", Some(3857)).unwrap();
+        assert!(
+            outside
+                .as_mvt_geom(bounds, 4096, 256, true)
+                .unwrap()
+                .is_none()
+        );
+        assert!(geom.as_mvt_geom(bounds, 0, 0, true).is_err());
+    }
+
+    #[test]
+    fn test_flip_coordinates_in_place() {
+        let mut geom = LWGeom::from_text("LINESTRING ZM (1 2 3 4,5 6 7 8)", None).unwrap();
+        geom.flip_coordinates_in_place();
+        assert_eq!(
+            geom.as_text(None).unwrap(),
+            "LINESTRING ZM (2 1 3 4,6 5 7 8)"
+        );
+        assert_eq!(geom.get_bbox_ref().xmax(), 6.0);
+        assert_eq!(geom.get_bbox_ref().ymax(), 5.0);
+    }
+
+    #[test]
+    fn test_geohash() {
+        let point = LWGeom::from_text("POINT(-126 48)", Some(4326)).unwrap();
+        assert_eq!(point.geohash(None).unwrap(), "c0w3hf1s70w3hf1s70w3");
+        assert_eq!(point.geohash(Some(5)).unwrap(), "c0w3h");
+
+        let outside = LWGeom::from_text("POINT(500 48)", None).unwrap();
+        assert!(outside.geohash(None).is_err());
+    }
+
+    #[test]
+    fn test_swap_ordinates() {
+        let line = LWGeom::from_text("LINESTRING ZM (1 2 3 4,5 6 7 8)", None).unwrap();
+        assert_eq!(
+            line.swap_ordinates(Ordinate::Z, Ordinate::M)
+                .unwrap()
+                .as_text(None)
+                .unwrap(),
+            "LINESTRING ZM (1 2 4 3,5 6 8 7)"
+        );
+
+        let swapped = line.swap_ordinates(Ordinate::X, Ordinate::Z).unwrap();
+        assert_eq!(
+            swapped.as_text(None).unwrap(),
+            "LINESTRING ZM (3 2 1 4,7 6 5 8)"
+        );
+        assert_eq!(swapped.get_bbox_ref().xmax(), 7.0);
+
+        let flat = LWGeom::from_text("POINT(1 2)", None).unwrap();
+        assert!(matches!(
+            flat.swap_ordinates(Ordinate::X, Ordinate::M),
+            Err(LWGeomError::InvalidParameterError(_, param)) if param == "b"
+        ));
+    }
+
+    #[test]
+    fn test_geos_concurrent() {
+        let square = std::sync::Arc::new(
+            LWGeom::from_text("POLYGON((0 0,0 10,10 10,10 0,0 0))", None).unwrap(),
+        );
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let square = square.clone();
+                std::thread::spawn(move || {
+                    let point = LWGeom::from_text(&format!("POINT({} 5)", i * 3), None).unwrap();
+                    for _ in 0..200 {
+                        assert_eq!(square.intersects(&point).unwrap(), i * 3 <= 10);
+                        assert!(square.relate(&point).unwrap().len() == 9);
+                        square.unary_union().unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_as_mvt_geom_collapse() {
+        let tile = LWGeom::from_text("POLYGON((0 0,0 10,10 10,10 0,0 0))", Some(3857)).unwrap();
+        let bounds = tile.get_bbox_ref();
+
+        // The spike is narrower than a pixel and folds onto itself on the grid.
+        let spike = LWGeom::from_text(
+            "POLYGON((0 0,10 0,10 10,5.0004 10,5.0004 2,4.9996 2,4.9996 10,0 10,0 0))",
+            Some(3857),
+        )
+        .unwrap();
+        let mvt = spike.as_mvt_geom(bounds, 4096, 0, true).unwrap().unwrap();
+        assert_eq!(mvt.describe().geom_type, "Polygon");
+        assert_eq!(mvt.area(), 4096.0 * 4096.0);
+        assert!(mvt.vertices().all(|v| v[1] != 3277.0));
+        let square =
+            LWGeom::from_text("POLYGON((0 0,0 4096,4096 4096,4096 0,0 0))", Some(3857)).unwrap();
+        assert!(mvt.equals(&square).unwrap());
+
+        let sliver =
+            LWGeom::from_text("POLYGON((5 5,5.001 5,5.001 8,5 8,5 5))", Some(3857)).unwrap();
+        assert!(sliver.as_mvt_geom(bounds, 4096, 0, true).unwrap().is_none());
+    }
 }
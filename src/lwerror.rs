@@ -0,0 +1,75 @@
+use core::ffi::CStr;
+use std::cell::RefCell;
+use std::sync::Once;
+
+use libc::c_char;
+use lwgeom_sys::*;
+
+use crate::LWGeomError;
+
+/// Names the `va_list` parameter type bindgen generated for `lwreporter`,
+/// which differs per target (a pointer on x86_64, a struct on aarch64).
+trait Reporter {
+    type VaList;
+}
+
+impl<V> Reporter for Option<unsafe extern "C" fn(*const c_char, V)> {
+    type VaList = V;
+}
+
+type VaList = <lwreporter as Reporter>::VaList;
+
+thread_local! {
+    static PENDING_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static HANDLERS: Once = Once::new();
+
+/// Installs an error reporter that records the message instead of exiting.
+/// Every constructor of an owned wrapper calls this, so no liblwgeom code
+/// runs against the default reporter.
+pub(crate) fn install() {
+    HANDLERS.call_once(|| unsafe {
+        lwgeom_set_handlers(None, None, None, Some(error_reporter), None);
+    });
+}
+
+/// Installs the reporter and clears any message left over from an earlier
+/// call on this thread; call before a liblwgeom function that can fail.
+pub(crate) fn init() {
+    install();
+    PENDING_ERROR.with(|error| error.borrow_mut().take());
+}
+
+/// Returns the most recent liblwgeom error message reported on this thread.
+pub fn last_error() -> Option<String> {
+    LAST_ERROR.with(|error| error.borrow().clone())
+}
+
+/// Turns a recorded liblwgeom message into `LibError`, or returns `fallback`.
+pub(crate) fn error(fallback: LWGeomError) -> LWGeomError {
+    match PENDING_ERROR.with(|error| error.borrow_mut().take()) {
+        Some(message) => LWGeomError::LibError(message),
+        None => fallback,
+    }
+}
+
+/// Keeps the first message, since liblwgeom often reports a follow-up
+/// error once the original failure has propagated.
+unsafe extern "C" fn error_reporter(fmt: *const c_char, ap: VaList) {
+    let mut buffer = [0 as c_char; 1024];
+    // Bound from the same headers as lwreporter, so it takes the same va_list.
+    vsnprintf(buffer.as_mut_ptr(), buffer.len() as _, fmt, ap);
+    let message = CStr::from_ptr(buffer.as_ptr())
+        .to_string_lossy()
+        .trim_end()
+        .to_owned();
+    let _ = PENDING_ERROR.try_with(|error| {
+        let mut error = error.borrow_mut();
+        if error.is_none() {
+            let _ = LAST_ERROR.try_with(|last| *last.borrow_mut() = Some(message.clone()));
+            *error = Some(message);
+        }
+    });
+}
@@ -7,9 +7,21 @@ use std::marker::PhantomData;
 use libc::{c_char, c_int};
 use lwgeom_sys::*;
 
+use crate::affine_matrix::AffineMatrix;
+use crate::buffer_params::BufferParams;
+use crate::geom_info::GeomInfo;
+use crate::geometry_iter::GeometryIter;
+use crate::geos::{self, GEOSGeom};
+use crate::grid_spec::GridSpec;
 use crate::lwgeom_parser_result::LWGeomParserResult;
-use crate::lwpoly::LWPoly;
-use crate::{GBoxRef, LWGeomError, Result};
+use crate::lwline::LWLineRef;
+use crate::lwpoint::LWPointRef;
+use crate::lwpoly::{LWPoly, LWPolyRef};
+use crate::ordinate::Ordinate;
+use crate::sfs_version::SfsVersion;
+use crate::spheroid::Spheroid;
+use crate::vertex_iter::VertexIter;
+use crate::{GBoxRef, LWGeomError, Result, TrajectoryError, lwerror};
 
 pub struct LWGeom(*mut LWGEOM);
 
@@ -19,17 +31,38 @@ impl LWGeom {
             !ptr.is_null(),
             "Attempted to create a LWGeom from a null pointer."
         );
+        lwerror::install();
         Self(ptr)
     }
 
     pub fn as_ptr(&self) -> *mut LWGEOM {
         self.0
     }
+
+    fn into_ptr(self) -> *mut LWGEOM {
+        let ptr = self.0;
+        core::mem::forget(self);
+        ptr
+    }
 }
 
 unsafe impl Send for LWGeom {}
 unsafe impl Sync for LWGeom {}
 
+impl Clone for LWGeom {
+    fn clone(&self) -> Self {
+        Self::from_ptr(unsafe { lwgeom_clone_deep(self.as_ptr()) })
+    }
+}
+
+/// Structural equality: same type, dimensions and vertices in the same
+/// order. See [`LWGeom::equals`] for spatial equality.
+impl PartialEq for LWGeom {
+    fn eq(&self, other: &Self) -> bool {
+        unsafe { lwgeom_same(self.as_ptr(), other.as_ptr()) != 0 }
+    }
+}
+
 impl Drop for LWGeom {
     fn drop(&mut self) {
         unsafe { lwgeom_free(self.as_ptr()) }
@@ -55,8 +88,27 @@ impl LWGeomRef {
 unsafe impl Send for LWGeomRef {}
 unsafe impl Sync for LWGeomRef {}
 
+impl<'a> IntoIterator for &'a LWGeom {
+    type IntoIter = GeometryIter<'a>;
+    type Item = &'a LWGeomRef;
+
+    fn into_iter(self) -> Self::IntoIter {
+        GeometryIter::new(LWGeomRef::from_ptr(self.as_ptr()))
+    }
+}
+
+impl<'a> IntoIterator for &'a LWGeomRef {
+    type IntoIter = GeometryIter<'a>;
+    type Item = &'a LWGeomRef;
+
+    fn into_iter(self) -> Self::IntoIter {
+        GeometryIter::new(self)
+    }
+}
+
 impl LWGeom {
     pub fn from_text(wkt: &str, srid: Option<i32>) -> Result<Self> {
+        lwerror::init();
         let c_wkt = CString::new(wkt)?;
         let p_parser_result = MaybeUninit::uninit().as_mut_ptr();
         let result = unsafe {
@@ -85,6 +137,7 @@ impl LWGeom {
     }
 
     pub fn from_ewkt(wkt: &str) -> Result<Self> {
+        lwerror::init();
         let c_wkt = CString::new(wkt)?;
         let p_parser_result = MaybeUninit::uninit().as_mut_ptr();
         let result = unsafe {
@@ -105,71 +158,224 @@ impl LWGeom {
     }
 
     pub fn from_ewkb(ewkb: &[u8]) -> Result<Self> {
+        lwerror::init();
         let p_geom =
             unsafe { lwgeom_from_wkb(ewkb.as_ptr(), ewkb.len(), LW_PARSER_CHECK_ALL as c_char) };
         if p_geom.is_null() {
-            return Err(LWGeomError::NullPtrError);
+            return Err(lwerror::error(LWGeomError::NullPtrError));
         }
 
         Ok(Self::from_ptr(p_geom))
     }
-}
 
-impl LWGeom {
-    pub fn as_text(&self, precision: Option<i32>) -> Result<String> {
-        let precision = precision.unwrap_or(15);
-        let mut sz = MaybeUninit::uninit();
-        let p_wkt =
-            unsafe { lwgeom_to_wkt(self.as_ptr(), WKT_ISO as u8, precision, sz.as_mut_ptr()) };
-        if p_wkt.is_null() {
-            return Err(LWGeomError::NullPtrError);
-        }
-
-        let c_wkt = unsafe {
-            CStr::from_bytes_with_nul_unchecked(core::slice::from_raw_parts(
-                p_wkt.cast(),
-                sz.assume_init(),
-            ))
+    /// Gathers copies of `geoms` into a multi geometry when they all share a
+    /// type, or into a GeometryCollection otherwise.
+    pub fn collect(geoms: &[LWGeom]) -> Result<Self> {
+        let Some(first) = geoms.first() else {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_Collect".to_owned(),
+                "geoms".to_owned(),
+            ));
         };
-        let text = c_wkt.to_string_lossy().into_owned();
-        unsafe {
-            lwfree(p_wkt.cast());
+        for geom in &geoms[1..] {
+            first.check_same_srid(geom, "ST_Collect")?;
+            if geom.has_z() != first.has_z() || geom.has_m() != first.has_m() {
+                return Err(LWGeomError::InvalidParameterError(
+                    "ST_Collect".to_owned(),
+                    "geoms".to_owned(),
+                ));
+            }
         }
-        Ok(text)
-    }
 
-    pub fn as_ewkt(&self, precision: Option<i32>) -> Result<String> {
-        let precision = precision.unwrap_or(15);
-        let mut sz = MaybeUninit::uninit();
-        let p_wkt = unsafe {
-            lwgeom_to_wkt(
-                self.as_ptr(),
-                WKT_EXTENDED as u8,
-                precision,
-                sz.as_mut_ptr(),
+        let geom_type = unsafe { (*first.as_ptr()).type_ };
+        let collection_type = if geoms
+            .iter()
+            .all(|geom| unsafe { (*geom.as_ptr()).type_ } == geom_type)
+        {
+            unsafe { lwtype_get_collectiontype(geom_type) }
+        } else {
+            COLLECTIONTYPE as u8
+        };
+
+        let p_geoms = unsafe { lwalloc(core::mem::size_of::<*mut LWGEOM>() * geoms.len()) }
+            .cast::<*mut LWGEOM>();
+        for (i, geom) in geoms.iter().enumerate() {
+            unsafe { *p_geoms.add(i) = lwgeom_clone_deep(geom.as_ptr()) };
+        }
+        let p_collection = unsafe {
+            lwcollection_construct(
+                collection_type,
+                lwgeom_get_srid(first.as_ptr()),
+                core::ptr::null_mut(),
+                geoms.len() as u32,
+                p_geoms,
             )
         };
-        if p_wkt.is_null() {
-            return Err(LWGeomError::NullPtrError);
+        Ok(Self::from_ptr(unsafe {
+            lwcollection_as_lwgeom(p_collection)
+        }))
+    }
+}
+
+fn to_wkt(p_geom: *const LWGEOM, variant: u8, precision: Option<i32>) -> Result<String> {
+    let precision = precision.unwrap_or(15);
+    let mut sz = MaybeUninit::uninit();
+    lwerror::init();
+    let p_wkt = unsafe { lwgeom_to_wkt(p_geom, variant, precision, sz.as_mut_ptr()) };
+    if p_wkt.is_null() {
+        return Err(lwerror::error(LWGeomError::NullPtrError));
+    }
+
+    let c_wkt = unsafe {
+        CStr::from_bytes_with_nul_unchecked(core::slice::from_raw_parts(
+            p_wkt.cast(),
+            sz.assume_init(),
+        ))
+    };
+    let wkt = c_wkt.to_string_lossy().into_owned();
+    unsafe {
+        lwfree(p_wkt.cast());
+    }
+    Ok(wkt)
+}
+
+/// Removes polygons whose outer ring liblwgeom held at its 4 point minimum
+/// although its area is below `threshold`, recursing into collections.
+/// Returns whether `p_geom` itself is such a polygon.
+fn drop_collapsed_polygons(p_geom: *mut LWGEOM, threshold: f64) -> bool {
+    let geom_type = unsafe { (*p_geom).type_ } as u32;
+    if geom_type == POLYGONTYPE {
+        let poly = unsafe { &*p_geom.cast::<LWPOLY>() };
+        if poly.nrings == 0 {
+            return false;
         }
+        let p_ring = unsafe { *poly.rings };
+        return unsafe { (*p_ring).npoints <= 4 && ptarray_signed_area(p_ring).abs() < threshold };
+    }
+    if unsafe { lwgeom_is_collection(p_geom) } == 0 {
+        return false;
+    }
 
-        let c_wkt = unsafe {
-            CStr::from_bytes_with_nul_unchecked(core::slice::from_raw_parts(
-                p_wkt.cast(),
-                sz.assume_init(),
-            ))
-        };
-        let ewkt = c_wkt.to_string_lossy().into_owned();
-        unsafe {
-            lwfree(p_wkt.cast());
+    let collection = unsafe { &mut *p_geom.cast::<LWCOLLECTION>() };
+    let mut kept = 0;
+    for i in 0..collection.ngeoms as usize {
+        let p_part = unsafe { *collection.geoms.add(i) };
+        if drop_collapsed_polygons(p_part, threshold) {
+            unsafe { lwgeom_free(p_part) };
+        } else {
+            unsafe { *collection.geoms.add(kept) = p_part };
+            kept += 1;
+        }
+    }
+    collection.ngeoms = kept as u32;
+    false
+}
+
+/// Point, line or polygon type of the highest-dimension parts, or 0 when the
+/// geometry has none of them.
+fn basic_type(p_geom: *const LWGEOM) -> u32 {
+    match unsafe { (*p_geom).type_ } as u32 {
+        POINTTYPE | MULTIPOINTTYPE => POINTTYPE,
+        LINETYPE | MULTILINETYPE => LINETYPE,
+        POLYGONTYPE | MULTIPOLYGONTYPE => POLYGONTYPE,
+        COLLECTIONTYPE => {
+            LWGeomRef::from_ptr(p_geom.cast_mut())
+                .into_iter()
+                .map(|part| basic_type(part.as_ptr()))
+                .max()
+                .unwrap_or(0)
+        }
+        _ => 0,
+    }
+}
+
+fn mem_size(p_geom: *const LWGEOM) -> usize {
+    use core::mem::size_of;
+
+    fn ptarray_mem_size(p_points: *const POINTARRAY) -> usize {
+        if p_points.is_null() {
+            return 0;
+        }
+
+        let points = unsafe { &*p_points };
+        let mut size = size_of::<POINTARRAY>();
+        if points.flags as u32 & LWFLAG_READONLY == 0 {
+            let ndims = 2
+                + (points.flags as u32 & LWFLAG_Z != 0) as usize
+                + (points.flags as u32 & LWFLAG_M != 0) as usize;
+            size += points.maxpoints as usize * ndims * size_of::<f64>();
+        }
+        size
+    }
+
+    let geom = unsafe { &*p_geom };
+    let mut size = if geom.bbox.is_null() {
+        0
+    } else {
+        size_of::<GBOX>()
+    };
+    match geom.type_ as u32 {
+        POINTTYPE => {
+            let point = unsafe { &*p_geom.cast::<LWPOINT>() };
+            size += size_of::<LWPOINT>() + ptarray_mem_size(point.point);
         }
-        Ok(ewkt)
+        LINETYPE | CIRCSTRINGTYPE | TRIANGLETYPE => {
+            let line = unsafe { &*p_geom.cast::<LWLINE>() };
+            size += size_of::<LWLINE>() + ptarray_mem_size(line.points);
+        }
+        POLYGONTYPE => {
+            let poly = unsafe { &*p_geom.cast::<LWPOLY>() };
+            size += size_of::<LWPOLY>() + poly.maxrings as usize * size_of::<*mut POINTARRAY>();
+            for i in 0..poly.nrings as usize {
+                size += ptarray_mem_size(unsafe { *poly.rings.add(i) });
+            }
+        }
+        // Every other type, curve polygons included, shares the collection layout.
+        _ => {
+            let collection = unsafe { &*p_geom.cast::<LWCOLLECTION>() };
+            size +=
+                size_of::<LWCOLLECTION>() + collection.maxgeoms as usize * size_of::<*mut LWGEOM>();
+            for i in 0..collection.ngeoms as usize {
+                size += mem_size(unsafe { *collection.geoms.add(i) });
+            }
+        }
+    }
+    size
+}
+
+impl LWGeom {
+    pub fn as_text(&self, precision: Option<i32>) -> Result<String> {
+        to_wkt(self.as_ptr(), WKT_ISO as u8, precision)
+    }
+
+    pub fn as_ewkt(&self, precision: Option<i32>) -> Result<String> {
+        to_wkt(self.as_ptr(), WKT_EXTENDED as u8, precision)
+    }
+
+    pub fn as_point(&self) -> Option<&LWPointRef> {
+        let p_point = unsafe { lwgeom_as_lwpoint(self.as_ptr()) };
+        (!p_point.is_null()).then(|| LWPointRef::from_ptr(p_point))
+    }
+
+    pub fn as_line(&self) -> Option<&LWLineRef> {
+        let p_line = unsafe { lwgeom_as_lwline(self.as_ptr()) };
+        (!p_line.is_null()).then(|| LWLineRef::from_ptr(p_line))
+    }
+
+    pub fn as_polygon(&self) -> Option<&LWPolyRef> {
+        let p_poly = unsafe { lwgeom_as_lwpoly(self.as_ptr()) };
+        (!p_poly.is_null()).then(|| LWPolyRef::from_ptr(p_poly))
+    }
+
+    pub fn vertices(&self) -> VertexIter<'_> {
+        VertexIter::new(LWGeomRef::from_ptr(self.as_ptr()))
     }
 
     pub fn as_ewkb(&self) -> Result<Vec<u8>> {
+        lwerror::init();
         let p_varlena = unsafe { lwgeom_to_wkb_varlena(self.as_ptr(), WKB_EXTENDED as u8) };
         if p_varlena.is_null() {
-            return Err(LWGeomError::NullPtrError);
+            return Err(lwerror::error(LWGeomError::NullPtrError));
         }
 
         let ewkb_slice = unsafe {
@@ -184,6 +390,29 @@ impl LWGeom {
         }
         Ok(ewkb)
     }
+
+    /// Geohash of a lon/lat geometry's bbox, `ST_GeoHash`. Without a
+    /// `precision` the longest hash that still covers the bbox is used.
+    pub fn geohash(&self, precision: Option<u32>) -> Result<String> {
+        let precision = precision.unwrap_or(0).min(c_int::MAX as u32) as c_int;
+        lwerror::init();
+        let p_varlena = unsafe { lwgeom_geohash(self.as_ptr(), precision) };
+        if p_varlena.is_null() {
+            return Err(lwerror::error(LWGeomError::CalculateError(
+                "ST_GeoHash".to_owned(),
+            )));
+        }
+
+        // The size word is a varlena header: total length shifted left by 2.
+        let len = unsafe { ((*p_varlena).size >> 2) & 0x3FFF_FFFF } as usize - 4;
+        let hash_slice =
+            unsafe { core::slice::from_raw_parts((*p_varlena).data.as_ptr().cast::<u8>(), len) };
+        let hash = String::from_utf8_lossy(hash_slice).into_owned();
+        unsafe {
+            lwfree(p_varlena.cast());
+        }
+        Ok(hash)
+    }
 }
 
 impl LWGeom {
@@ -203,109 +432,1895 @@ impl LWGeom {
         unsafe { lwgeom_set_srid(self.as_ptr(), srid) }
     }
 
-    pub fn split(&self, blade: &LWGeom) -> Self {
-        let p_geom = unsafe { lwgeom_split(self.as_ptr(), blade.as_ptr()) };
-        Self::from_ptr(p_geom)
+    pub fn with_srid(mut self, srid: i32) -> Self {
+        self.set_srid(srid);
+        self
     }
 
-    pub fn get_bbox_ref(&self) -> &GBoxRef {
-        let p_bbox = unsafe { lwgeom_get_bbox(self.as_ptr()) };
-        GBoxRef::from_ptr(p_bbox.cast_mut())
+    pub fn is_empty(&self) -> bool {
+        unsafe { lwgeom_is_empty(self.as_ptr()) != 0 }
     }
 
-    pub fn tile_envelope(
-        zoom: i32, x: i32, y: i32, bounds: Option<&LWGeom>, margin: Option<f64>,
-    ) -> Result<Self> {
-        let bounds2 =   Self::from_ewkt("SRID=3857;LINESTRING(-20037508.342789 -20037508.342789,20037508.342789 20037508.342789)").unwrap() ;
-        let bounds = match bounds {
-            Some(bounds) => bounds,
-            None => &bounds2,
-        };
-        let bbox = bounds.get_bbox_ref();
+    pub fn has_z(&self) -> bool {
+        unsafe { lwgeom_has_z(self.as_ptr()) != 0 }
+    }
 
-        let srid = bounds.get_srid().unwrap_or(3857);
+    pub fn has_m(&self) -> bool {
+        unsafe { lwgeom_has_m(self.as_ptr()) != 0 }
+    }
 
-        let margin = margin.unwrap_or(0.0);
-        if margin < -0.5 {
-            return Err(LWGeomError::InvalidParameterError(
-                "ST_TileEnvelope".to_owned(),
-                "margin".to_owned(),
-            ));
+    pub fn is_3d(&self) -> bool {
+        self.has_z()
+    }
+
+    pub fn is_measured(&self) -> bool {
+        self.has_m()
+    }
+
+    pub fn ndims(&self) -> u8 {
+        unsafe { lwgeom_ndims(self.as_ptr()) as u8 }
+    }
+
+    pub fn count_vertices(&self) -> usize {
+        unsafe { lwgeom_count_vertices(self.as_ptr()) as usize }
+    }
+
+    /// Topological dimension: 0 for points, 1 for lines, 2 for areas and the
+    /// highest member dimension for collections. Empty geometries have none.
+    pub fn dimension(&self) -> Option<i32> {
+        if self.is_empty() {
+            return None;
         }
 
-        let bounds_width = bbox.xmax() - bbox.xmin();
-        let bounds_height = bbox.ymax() - bbox.ymin();
-        if bounds_width <= 0.0 || bounds_height <= 0.0 {
+        let dimension = unsafe { lwgeom_dimension(self.as_ptr()) };
+        (dimension >= 0).then_some(dimension)
+    }
+
+    /// Bytes held by the in-memory geometry tree: headers, cached bbox,
+    /// ring and member arrays, and point storage.
+    pub fn mem_size(&self) -> usize {
+        mem_size(self.as_ptr())
+    }
+
+    /// Planar area of the areal members; points and lines contribute 0.
+    /// Curve polygons are stroked with 32 segments per quarter circle first.
+    pub fn area(&self) -> f64 {
+        unsafe { lwgeom_area(self.as_ptr()) }
+    }
+
+    /// Length of the linear members, following arcs for curved types; points
+    /// and areas contribute 0.
+    pub fn length(&self) -> f64 {
+        unsafe { lwgeom_length_2d(self.as_ptr()) }
+    }
+
+    pub fn length_3d(&self) -> f64 {
+        unsafe { lwgeom_length(self.as_ptr()) }
+    }
+
+    /// Area in square meters of a lon/lat geometry on `spheroid`.
+    pub fn area_spheroid(&self, spheroid: &Spheroid) -> Result<f64> {
+        let geom = self.to_geodetic("ST_Area")?;
+        Ok(unsafe { lwgeom_area_spheroid(geom.as_ptr(), spheroid.as_ptr()) })
+    }
+
+    /// Area in square meters of a lon/lat geometry on a sphere with the
+    /// authalic radius of `spheroid`, so the whole sphere has the spheroid's
+    /// surface area.
+    pub fn area_sphere(&self, spheroid: &Spheroid) -> Result<f64> {
+        let geom = self.to_geodetic("ST_Area")?;
+        let sphere = spheroid.authalic();
+        Ok(unsafe { lwgeom_area_sphere(geom.as_ptr(), sphere.as_ptr()) })
+    }
+
+    /// Length in meters of a lon/lat geometry on `spheroid`; areal members
+    /// contribute their perimeter.
+    pub fn length_spheroid(&self, spheroid: &Spheroid) -> Result<f64> {
+        let geom = self.to_geodetic("ST_LengthSpheroid")?;
+        Ok(unsafe { lwgeom_length_spheroid(geom.as_ptr(), spheroid.as_ptr()) })
+    }
+
+    /// Minimum distance in meters between lon/lat geometries on `spheroid`.
+    pub fn distance_spheroid(&self, other: &LWGeom, spheroid: &Spheroid) -> Result<f64> {
+        self.check_distance_args(other, "ST_DistanceSpheroid")?;
+        let (geom1, geom2) = (
+            self.to_geodetic("ST_DistanceSpheroid")?,
+            other.to_geodetic("ST_DistanceSpheroid")?,
+        );
+        Ok(unsafe {
+            lwgeom_distance_spheroid(geom1.as_ptr(), geom2.as_ptr(), spheroid.as_ptr(), 0.0)
+        })
+    }
+
+    /// Whether lon/lat geometries are within `dist` meters on `spheroid`,
+    /// stopping at the first pair of elements found within range.
+    pub fn dwithin_spheroid(&self, other: &LWGeom, dist: f64, spheroid: &Spheroid) -> Result<bool> {
+        self.check_same_srid(other, "ST_DWithin")?;
+        if dist < 0.0 {
             return Err(LWGeomError::InvalidParameterError(
-                "ST_TileEnvelope".to_owned(),
-                "bounds".to_owned(),
+                "ST_DWithin".to_owned(),
+                "dist".to_owned(),
             ));
         }
+        if self.is_empty() || other.is_empty() {
+            return Ok(false);
+        }
 
-        if !(0..32).contains(&zoom) {
+        let (geom1, geom2) = (
+            self.to_geodetic("ST_DWithin")?,
+            other.to_geodetic("ST_DWithin")?,
+        );
+        let distance = unsafe {
+            lwgeom_distance_spheroid(geom1.as_ptr(), geom2.as_ptr(), spheroid.as_ptr(), dist)
+        };
+        Ok(distance <= dist)
+    }
+
+    /// Point `distance_m` meters from a lon/lat point along `azimuth_rad`,
+    /// clockwise from north. A negative distance projects along the reverse
+    /// bearing, and the result's longitude is normalized into -180..180.
+    pub fn project(&self, distance_m: f64, azimuth_rad: f64, spheroid: &Spheroid) -> Result<Self> {
+        if self.as_point().is_none() {
             return Err(LWGeomError::InvalidParameterError(
-                "ST_TileEnvelope".to_owned(),
-                "zoom".to_owned(),
+                "ST_Project".to_owned(),
+                "geom".to_owned(),
             ));
         }
-
-        let world_tile_size = 1 << zoom.min(31);
-        if x < 0 || x >= world_tile_size {
+        if self.is_empty() {
+            return Err(LWGeomError::EmptyGeometryError("ST_Project".to_owned()));
+        }
+        if distance_m.abs() > core::f64::consts::PI * spheroid.radius() {
             return Err(LWGeomError::InvalidParameterError(
-                "ST_TileEnvelope".to_owned(),
-                "x".to_owned(),
+                "ST_Project".to_owned(),
+                "distance".to_owned(),
             ));
         }
-        if y < 0 || y >= world_tile_size {
+
+        let geom = self.to_geodetic("ST_Project")?;
+        lwerror::init();
+        let p_point = unsafe {
+            lwgeom_project_spheroid(
+                lwgeom_as_lwpoint(geom.as_ptr()),
+                spheroid.as_ptr(),
+                distance_m,
+                azimuth_rad,
+            )
+        };
+        if p_point.is_null() {
+            return Err(lwerror::error(LWGeomError::CalculateError(
+                "ST_Project".to_owned(),
+            )));
+        }
+
+        let geom = Self::from_ptr(unsafe { lwpoint_as_lwgeom(p_point) });
+        unsafe { lwgeom_set_geodetic(geom.as_ptr(), LW_FALSE as c_int) };
+        Ok(geom)
+    }
+
+    /// Adds vertices along great circles so no segment of a lon/lat geometry
+    /// spans more than `max_segment_radians`. Edges crossing the antimeridian
+    /// are densified the short way round, with longitudes kept in -180..180.
+    pub fn segmentize_sphere(&self, max_segment_radians: f64) -> Result<Self> {
+        if max_segment_radians.is_nan() || max_segment_radians <= 0.0 {
             return Err(LWGeomError::InvalidParameterError(
-                "ST_TileEnvelope".to_owned(),
-                "y".to_owned(),
+                "ST_Segmentize".to_owned(),
+                "max_segment_radians".to_owned(),
             ));
         }
 
-        let tile_geo_size_x = bounds_width / world_tile_size as f64;
-        let tile_geo_size_y = bounds_height / world_tile_size as f64;
+        let geom = self.to_geodetic("ST_Segmentize")?;
+        lwerror::init();
+        let p_geom = unsafe { lwgeom_segmentize_sphere(geom.as_ptr(), max_segment_radians) };
+        if p_geom.is_null() {
+            return Err(lwerror::error(LWGeomError::NullPtrError));
+        }
 
-        let (x1, x2) = if (1.0 + margin * 2.0) > world_tile_size as f64 {
-            (bbox.xmin(), bbox.xmax())
-        } else {
-            (
-                bbox.xmin() + tile_geo_size_x * (x as f64 - margin),
-                bbox.xmin() + tile_geo_size_x * (x as f64 + 1.0 + margin),
-            )
-        };
-        let mut y1 = bbox.ymax() - tile_geo_size_y * (y as f64 + 1.0 + margin);
-        let mut y2 = bbox.ymax() - tile_geo_size_y * (y as f64 - margin);
-        if y1 < bbox.ymin() {
-            y1 = bbox.ymin()
+        let geom = Self::from_ptr(p_geom);
+        unsafe {
+            lwgeom_set_geodetic(geom.as_ptr(), LW_FALSE as c_int);
+            lwgeom_refresh_bbox(geom.as_ptr());
         }
-        if y2 > bbox.ymax() {
-            y2 = bbox.ymax()
+        Ok(geom)
+    }
+
+    /// Copy flagged as geodetic with geodetic bboxes, after checking that the
+    /// coordinates are within lon/lat bounds.
+    fn to_geodetic(&self, function: &str) -> Result<Self> {
+        if !self.is_empty() {
+            let bbox = self.get_bbox_ref();
+            for (value, limit) in [
+                (bbox.xmin(), 180.0),
+                (bbox.xmax(), 180.0),
+                (bbox.ymin(), 90.0),
+                (bbox.ymax(), 90.0),
+            ] {
+                if !(-limit..=limit).contains(&value) {
+                    return Err(LWGeomError::CoordinateOutOfRangeError(
+                        function.to_owned(),
+                        value,
+                    ));
+                }
+            }
         }
 
-        Ok(LWPoly::construct_envelope(srid, x1, y1, x2, y2).into_lwgeom())
+        let geom = self.clone();
+        unsafe {
+            lwgeom_drop_bbox(geom.as_ptr());
+            lwgeom_set_geodetic(geom.as_ptr(), LW_TRUE as c_int);
+            lwgeom_add_bbox_deep(geom.as_ptr(), core::ptr::null_mut());
+        }
+        Ok(geom)
     }
-}
 
-impl LWGeomRef {
-    pub fn has_srid(&self) -> bool {
-        unsafe { lwgeom_has_srid(self.as_ptr()) != 0 }
+    /// Boundary length of the areal members, inner rings included; points and
+    /// lines contribute 0.
+    pub fn perimeter(&self) -> f64 {
+        unsafe { lwgeom_perimeter_2d(self.as_ptr()) }
     }
 
-    pub fn get_srid(&self) -> Option<i32> {
-        if self.has_srid() {
-            Some(unsafe { lwgeom_get_srid(self.as_ptr()) })
+    pub fn perimeter_3d(&self) -> f64 {
+        unsafe { lwgeom_perimeter(self.as_ptr()) }
+    }
+
+    pub fn describe(&self) -> GeomInfo {
+        let geom_type = unsafe { CStr::from_ptr(lwtype_name((*self.as_ptr()).type_)) };
+        GeomInfo {
+            geom_type: geom_type.to_string_lossy().into_owned(),
+            srid: self.get_srid(),
+            has_z: self.has_z(),
+            has_m: self.has_m(),
+            is_geodetic: unsafe { lwgeom_is_geodetic(self.as_ptr()) != 0 },
+            has_bbox: unsafe { !(*self.as_ptr()).bbox.is_null() },
+            num_geometries: self.num_geometries(),
+            num_vertices: self.count_vertices(),
+        }
+    }
+
+    pub fn num_geometries(&self) -> usize {
+        let p_collection = unsafe { lwgeom_as_lwcollection(self.as_ptr()) };
+        if !p_collection.is_null() {
+            unsafe { (*p_collection).ngeoms as usize }
+        } else if self.is_empty() {
+            0
         } else {
-            None
+            1
         }
     }
 
-    pub fn set_srid(&mut self, srid: i32) {
-        unsafe { lwgeom_set_srid(self.as_ptr(), srid) }
+    /// Returns the `n`th member of a collection, counting from 0. A non-empty
+    /// single geometry is its own only member.
+    pub fn geometry_n(&self, n: usize) -> Option<&LWGeomRef> {
+        if n >= self.num_geometries() {
+            return None;
+        }
+
+        let p_collection = unsafe { lwgeom_as_lwcollection(self.as_ptr()) };
+        if p_collection.is_null() {
+            Some(LWGeomRef::from_ptr(self.as_ptr()))
+        } else {
+            Some(LWGeomRef::from_ptr(unsafe {
+                *(*p_collection).geoms.add(n)
+            }))
+        }
     }
 
-    pub fn get_bbox_ref(&self) -> &GBoxRef {
-        let p_bbox = unsafe { lwgeom_get_bbox(self.as_ptr()) };
-        GBoxRef::from_ptr(p_bbox.cast_mut())
+    pub fn geometries(&self) -> GeometryIter<'_> {
+        self.into_iter()
+    }
+
+    pub fn split(&self, blade: &LWGeom) -> Self {
+        let _geos = geos::lock();
+        let p_geom = unsafe { lwgeom_split(self.as_ptr(), blade.as_ptr()) };
+        Self::from_ptr(p_geom)
+    }
+
+    /// Closure is checked in 3D when the geometry has Z. Compound curves,
+    /// polygons and polyhedral surfaces use their own closure rules.
+    pub fn is_closed(&self) -> bool {
+        !self.is_empty() && unsafe { lwgeom_is_closed(self.as_ptr()) != 0 }
+    }
+
+    pub fn is_ring(&self) -> Result<bool> {
+        match unsafe { (*self.as_ptr()).type_ } as u32 {
+            LINETYPE | CIRCSTRINGTYPE | COMPOUNDTYPE => {}
+            _ => {
+                return Err(LWGeomError::InvalidParameterError(
+                    "ST_IsRing".to_owned(),
+                    "geom".to_owned(),
+                ));
+            }
+        }
+        if !self.is_closed() {
+            return Ok(false);
+        }
+
+        let _geos = geos::lock();
+        let simple = unsafe { lwgeom_is_simple(self.as_ptr()) };
+        if simple == -1 {
+            return Err(lwerror::error(LWGeomError::CalculateError(
+                "ST_IsRing".to_owned(),
+            )));
+        }
+
+        Ok(simple != 0)
     }
+
+    /// A trajectory is a LineString with M whose measures strictly increase.
+    pub fn is_trajectory(&self) -> bool {
+        unsafe { lwgeom_is_trajectory(self.as_ptr()) != 0 }
+    }
+
+    pub fn validate_trajectory(&self) -> core::result::Result<(), TrajectoryError> {
+        if unsafe { (*self.as_ptr()).type_ } as u32 != LINETYPE {
+            return Err(TrajectoryError::NotLineStringError);
+        }
+        if !self.has_m() {
+            return Err(TrajectoryError::MissingMeasureError);
+        }
+
+        let p_points = unsafe { (*self.as_ptr().cast::<LWLINE>()).points };
+        let npoints = unsafe { (*p_points).npoints };
+        let mut prev_m = f64::NEG_INFINITY;
+        for i in 0..npoints {
+            let mut point = MaybeUninit::uninit();
+            let m = unsafe {
+                getPoint4d_p(p_points, i, point.as_mut_ptr());
+                point.assume_init().m
+            };
+            if i > 0 && m <= prev_m {
+                return Err(TrajectoryError::NonIncreasingMeasureError(i as usize));
+            }
+            prev_m = m;
+        }
+        Ok(())
+    }
+
+    /// Returns the time (M value) at which two trajectories are closest and
+    /// their distance at that time.
+    pub fn tcpa(&self, other: &LWGeom) -> Result<(f64, f64)> {
+        self.check_common_time_range(other, "ST_ClosestPointOfApproach")?;
+        let mut distance = 0.0;
+        let time = unsafe { lwgeom_tcpa(self.as_ptr(), other.as_ptr(), &mut distance) };
+        if time < 0.0 {
+            return Err(LWGeomError::DisjointTrajectoriesError);
+        }
+
+        Ok((time, distance))
+    }
+
+    pub fn cpa_within(&self, other: &LWGeom, max_dist: f64) -> Result<bool> {
+        if max_dist < 0.0 {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_CPAWithin".to_owned(),
+                "max_dist".to_owned(),
+            ));
+        }
+
+        self.check_common_time_range(other, "ST_CPAWithin")?;
+        Ok(unsafe { lwgeom_cpa_within(self.as_ptr(), other.as_ptr(), max_dist) != 0 })
+    }
+
+    fn check_common_time_range(&self, other: &LWGeom, function: &str) -> Result<()> {
+        self.validate_trajectory()?;
+        other.validate_trajectory()?;
+        let (Some((start1, end1)), Some((start2, end2))) =
+            (self.measure_range(), other.measure_range())
+        else {
+            return Err(LWGeomError::InvalidParameterError(
+                function.to_owned(),
+                "geom".to_owned(),
+            ));
+        };
+
+        if start1 > end2 || start2 > end1 {
+            return Err(LWGeomError::DisjointTrajectoriesError);
+        }
+        Ok(())
+    }
+
+    /// First and last M value of a validated trajectory.
+    fn measure_range(&self) -> Option<(f64, f64)> {
+        let p_points = unsafe { (*self.as_ptr().cast::<LWLINE>()).points };
+        let npoints = unsafe { (*p_points).npoints };
+        let m = |n| {
+            let mut point = MaybeUninit::uninit();
+            unsafe {
+                getPoint4d_p(p_points, n, point.as_mut_ptr());
+                point.assume_init().m
+            }
+        };
+        Some((m(0), m(npoints.checked_sub(1)?)))
+    }
+
+    pub fn snap(&self, reference: &LWGeom, tolerance: f64) -> Result<Self> {
+        let _geos = geos::lock();
+        let p_geom = unsafe { lwgeom_snap(self.as_ptr(), reference.as_ptr(), tolerance) };
+        if p_geom.is_null() {
+            return Err(lwerror::error(LWGeomError::NullPtrError));
+        }
+
+        Ok(Self::from_ptr(p_geom))
+    }
+
+    pub fn node(&self) -> Result<Self> {
+        let _geos = geos::lock();
+        let p_geom = unsafe { lwgeom_node(self.as_ptr()) };
+        if p_geom.is_null() {
+            return Err(lwerror::error(LWGeomError::NullPtrError));
+        }
+
+        Ok(Self::from_ptr(p_geom))
+    }
+
+    /// Douglas-Peucker simplification. Inner rings that collapse are dropped;
+    /// with `preserve_collapsed` lines and outer rings that would collapse are
+    /// kept at their minimal size instead. A fully collapsed result is an
+    /// empty geometry of the input type.
+    pub fn simplify(&self, tolerance: f64, preserve_collapsed: bool) -> Result<Self> {
+        if tolerance < 0.0 {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_Simplify".to_owned(),
+                "tolerance".to_owned(),
+            ));
+        }
+
+        let p_geom =
+            unsafe { lwgeom_simplify(self.as_ptr(), tolerance, preserve_collapsed as c_int) };
+        if p_geom.is_null() {
+            return self.empty_like();
+        }
+
+        Ok(Self::from_ptr(p_geom))
+    }
+
+    /// Visvalingam-Whyatt effective areas: keeps the vertices whose effective
+    /// area reaches `threshold` and stores that area in M, overwriting any
+    /// existing M values. `preserve_collapsed` works as in
+    /// [`Self::simplify_vw`].
+    pub fn set_effective_area(&self, threshold: f64, preserve_collapsed: bool) -> Result<Self> {
+        self.effective_area(true, threshold, preserve_collapsed, "ST_SetEffectiveArea")
+    }
+
+    /// Visvalingam-Whyatt simplification, dropping vertices whose effective
+    /// area is below `threshold`. Lines keep their end points and inner rings
+    /// that collapse are dropped. Outer rings never shrink below 4 points; with
+    /// `preserve_collapsed` such a ring is kept at that size, otherwise a ring
+    /// smaller than `threshold` counts as collapsed and its polygon is
+    /// removed. A fully collapsed polygon is an empty polygon.
+    pub fn simplify_vw(&self, threshold: f64, preserve_collapsed: bool) -> Result<Self> {
+        self.effective_area(false, threshold, preserve_collapsed, "ST_SimplifyVW")
+    }
+
+    fn effective_area(
+        &self, set_area: bool, threshold: f64, preserve_collapsed: bool, function: &str,
+    ) -> Result<Self> {
+        if threshold < 0.0 {
+            return Err(LWGeomError::InvalidParameterError(
+                function.to_owned(),
+                "threshold".to_owned(),
+            ));
+        }
+
+        lwerror::init();
+        let p_geom =
+            unsafe { lwgeom_set_effective_area(self.as_ptr(), set_area as c_int, threshold) };
+        if p_geom.is_null() {
+            return Err(lwerror::error(LWGeomError::NullPtrError));
+        }
+
+        let geom = Self::from_ptr(p_geom);
+        if preserve_collapsed {
+            return Ok(geom);
+        }
+        if drop_collapsed_polygons(geom.as_ptr(), threshold) {
+            return geom.empty_like();
+        }
+
+        unsafe { lwgeom_refresh_bbox(geom.as_ptr()) };
+        Ok(geom)
+    }
+
+    /// Chaikin corner cutting, run 1 to 5 times. `preserve_end_points` keeps
+    /// the ends of lines in place; rings always stay closed.
+    pub fn chaikin_smooth(&self, iterations: u32, preserve_end_points: bool) -> Result<Self> {
+        if !(1..=5).contains(&iterations) {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_ChaikinSmoothing".to_owned(),
+                "iterations".to_owned(),
+            ));
+        }
+
+        lwerror::init();
+        let p_geom = unsafe {
+            lwgeom_chaikin(
+                self.as_ptr(),
+                iterations as c_int,
+                preserve_end_points as c_int,
+            )
+        };
+        if p_geom.is_null() {
+            return Err(lwerror::error(LWGeomError::NullPtrError));
+        }
+
+        Ok(Self::from_ptr(p_geom))
+    }
+
+    /// Adds vertices so no segment is longer than `max_segment_length`, with Z
+    /// and M interpolated linearly. Lengths that would produce more than
+    /// 2^24 vertices are rejected.
+    pub fn segmentize(&self, max_segment_length: f64) -> Result<Self> {
+        let invalid = || {
+            LWGeomError::InvalidParameterError(
+                "ST_Segmentize".to_owned(),
+                "max_segment_length".to_owned(),
+            )
+        };
+        if max_segment_length.is_nan() || max_segment_length <= 0.0 {
+            return Err(invalid());
+        }
+        let estimated_vertices =
+            (self.length() + self.perimeter()) / max_segment_length + self.count_vertices() as f64;
+        if estimated_vertices > (1 << 24) as f64 {
+            return Err(invalid());
+        }
+
+        lwerror::init();
+        let p_geom = unsafe { lwgeom_segmentize2d(self.as_ptr(), max_segment_length) };
+        if p_geom.is_null() {
+            return Err(lwerror::error(LWGeomError::NullPtrError));
+        }
+
+        Ok(Self::from_ptr(p_geom))
+    }
+
+    /// Drops consecutive vertices within `tolerance` of the previous kept one,
+    /// exact duplicates only for 0. Lines keep at least 2 points and rings at
+    /// least 4, closing point included.
+    pub fn remove_repeated_points(&self, tolerance: f64) -> Result<Self> {
+        let mut geom = self.clone();
+        geom.remove_repeated_points_in_place(tolerance)?;
+        Ok(geom)
+    }
+
+    pub fn remove_repeated_points_in_place(&mut self, tolerance: f64) -> Result<()> {
+        if tolerance.is_nan() || tolerance < 0.0 {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_RemoveRepeatedPoints".to_owned(),
+                "tolerance".to_owned(),
+            ));
+        }
+
+        unsafe {
+            lwgeom_remove_repeated_points_in_place(self.as_ptr(), tolerance);
+            lwgeom_refresh_bbox(self.as_ptr());
+        }
+        Ok(())
+    }
+
+    /// Snaps X and Y to the grid, `ST_SnapToGrid`. Repeated points are
+    /// removed, and lines or rings that collapse are dropped, possibly
+    /// leaving an empty geometry.
+    pub fn snap_to_grid(
+        &self, origin_x: f64, origin_y: f64, size_x: f64, size_y: f64,
+    ) -> Result<Self> {
+        self.snap_to_grid_spec(&GridSpec {
+            origin_x,
+            origin_y,
+            size_x,
+            size_y,
+            ..Default::default()
+        })
+    }
+
+    pub fn snap_to_grid_spec(&self, grid: &GridSpec) -> Result<Self> {
+        if !grid.is_valid() {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_SnapToGrid".to_owned(),
+                "grid".to_owned(),
+            ));
+        }
+
+        let geom = self.clone();
+        let grid = grid.to_gridspec();
+        unsafe {
+            lwgeom_grid_in_place(geom.as_ptr(), &grid);
+            lwgeom_refresh_bbox(geom.as_ptr());
+        }
+        Ok(geom)
+    }
+
+    pub fn unary_union(&self) -> Result<Self> {
+        let _geos = geos::lock();
+        let p_geom = unsafe { lwgeom_unaryunion(self.as_ptr()) };
+        if p_geom.is_null() {
+            return Err(lwerror::error(LWGeomError::NullPtrError));
+        }
+
+        Ok(Self::from_ptr(p_geom))
+    }
+
+    /// Offsets linear geometries by `distance`, to the left when positive and
+    /// to the right when negative.
+    pub fn offset_curve(&self, distance: f64, params: &BufferParams) -> Result<Self> {
+        // Collections are offset member by member, so every member must be linear.
+        fn is_linear(geom: &LWGeomRef) -> bool {
+            match unsafe { (*geom.as_ptr()).type_ } as u32 {
+                LINETYPE | MULTILINETYPE => true,
+                COLLECTIONTYPE => geom.into_iter().all(is_linear),
+                _ => false,
+            }
+        }
+
+        if !is_linear(LWGeomRef::from_ptr(self.as_ptr())) {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_OffsetCurve".to_owned(),
+                "geom".to_owned(),
+            ));
+        }
+
+        let _geos = geos::lock();
+        let p_geom = unsafe {
+            lwgeom_offsetcurve(
+                self.as_ptr(),
+                distance,
+                params.quad_segs,
+                params.join_style as c_int,
+                params.mitre_limit,
+            )
+        };
+        if p_geom.is_null() {
+            return Err(lwerror::error(LWGeomError::NullPtrError));
+        }
+
+        Ok(Self::from_ptr(p_geom))
+    }
+
+    pub fn clip_by_rect(&self, xmin: f64, ymin: f64, xmax: f64, ymax: f64) -> Result<Self> {
+        let _geos = geos::lock();
+        let p_geom = unsafe { lwgeom_clip_by_rect(self.as_ptr(), xmin, ymin, xmax, ymax) };
+        if p_geom.is_null() {
+            return Err(lwerror::error(LWGeomError::NullPtrError));
+        }
+
+        Ok(Self::from_ptr(p_geom))
+    }
+
+    pub fn line_interpolate_point(&self, fraction: f64, repeat: bool) -> Result<Self> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_LineInterpolatePoint".to_owned(),
+                "fraction".to_owned(),
+            ));
+        }
+
+        let p_line = unsafe { lwgeom_as_lwline(self.as_ptr()) };
+        if p_line.is_null() {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_LineInterpolatePoint".to_owned(),
+                "geom".to_owned(),
+            ));
+        }
+
+        let srid = unsafe { lwgeom_get_srid(self.as_ptr()) };
+        if self.is_empty() {
+            let p_point = unsafe {
+                lwpoint_construct_empty(srid, self.has_z() as c_char, self.has_m() as c_char)
+            };
+            return Ok(Self::from_ptr(unsafe { lwpoint_as_lwgeom(p_point) }));
+        }
+
+        lwerror::init();
+        let p_points = unsafe { lwline_interpolate_points(p_line, fraction, repeat as c_char) };
+        if p_points.is_null() {
+            return Err(lwerror::error(LWGeomError::NullPtrError));
+        }
+
+        let p_geom = unsafe {
+            if (*p_points).npoints <= 1 {
+                lwpoint_as_lwgeom(lwpoint_construct(srid, core::ptr::null_mut(), p_points))
+            } else {
+                let p_mpoint = lwmpoint_construct(srid, p_points);
+                ptarray_free(p_points);
+                lwmpoint_as_lwgeom(p_mpoint)
+            }
+        };
+        Ok(Self::from_ptr(p_geom))
+    }
+
+    pub fn line_substring(&self, from: f64, to: f64) -> Result<Self> {
+        if !(0.0..=1.0).contains(&from) {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_LineSubstring".to_owned(),
+                "from".to_owned(),
+            ));
+        }
+        if !(from..=1.0).contains(&to) {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_LineSubstring".to_owned(),
+                "to".to_owned(),
+            ));
+        }
+
+        let p_line = unsafe { lwgeom_as_lwline(self.as_ptr()) };
+        if p_line.is_null() {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_LineSubstring".to_owned(),
+                "geom".to_owned(),
+            ));
+        }
+
+        let srid = unsafe { lwgeom_get_srid(self.as_ptr()) };
+        if self.is_empty() {
+            let p_empty = unsafe {
+                lwline_construct_empty(srid, self.has_z() as c_char, self.has_m() as c_char)
+            };
+            return Ok(Self::from_ptr(unsafe { lwline_as_lwgeom(p_empty) }));
+        }
+
+        lwerror::init();
+        let p_points = unsafe { ptarray_substring((*p_line).points, from, to, 0.0) };
+        if p_points.is_null() {
+            return Err(lwerror::error(LWGeomError::NullPtrError));
+        }
+
+        let p_geom = unsafe {
+            if (*p_points).npoints == 1 {
+                lwpoint_as_lwgeom(lwpoint_construct(srid, core::ptr::null_mut(), p_points))
+            } else {
+                lwline_as_lwgeom(lwline_construct(srid, core::ptr::null_mut(), p_points))
+            }
+        };
+        Ok(Self::from_ptr(p_geom))
+    }
+
+    pub fn start_point(&self) -> Option<Self> {
+        self.linear_point(0)
+    }
+
+    pub fn end_point(&self) -> Option<Self> {
+        let p_line = self.as_linear_ptr()?;
+        let npoints = unsafe { (*(*p_line).points).npoints };
+        self.linear_point(npoints.checked_sub(1)?)
+    }
+
+    /// Returns the `n`th vertex of a LineString or CircularString, counting
+    /// from 1 like `ST_PointN`. Negative indexes count back from the end, so
+    /// `-1` is the last vertex.
+    pub fn point_n(&self, n: isize) -> Option<Self> {
+        let p_line = self.as_linear_ptr()?;
+        let npoints = unsafe { (*(*p_line).points).npoints } as isize;
+        let index = match n {
+            1.. => n - 1,
+            ..=-1 => npoints + n,
+            0 => return None,
+        };
+        if !(0..npoints).contains(&index) {
+            return None;
+        }
+
+        self.linear_point(index as u32)
+    }
+
+    /// LineString and CircularString share the `LWLINE` layout.
+    fn as_linear_ptr(&self) -> Option<*mut LWLINE> {
+        match unsafe { (*self.as_ptr()).type_ } as u32 {
+            LINETYPE | CIRCSTRINGTYPE => Some(self.as_ptr().cast()),
+            _ => None,
+        }
+    }
+
+    fn linear_point(&self, n: u32) -> Option<Self> {
+        let p_point = unsafe { lwline_get_lwpoint(self.as_linear_ptr()?, n) };
+        if p_point.is_null() {
+            return None;
+        }
+
+        Some(Self::from_ptr(unsafe { lwpoint_as_lwgeom(p_point) }))
+    }
+
+    pub fn line_locate_point(&self, point: &LWGeom) -> Result<f64> {
+        let p_line = unsafe { lwgeom_as_lwline(self.as_ptr()) };
+        if p_line.is_null() || self.is_empty() {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_LineLocatePoint".to_owned(),
+                "geom".to_owned(),
+            ));
+        }
+
+        let p_point = unsafe { lwgeom_as_lwpoint(point.as_ptr()) };
+        let mut p = MaybeUninit::uninit();
+        if p_point.is_null()
+            || unsafe { lwpoint_getPoint4d_p(p_point, p.as_mut_ptr()) } == LW_FAILURE as c_int
+        {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_LineLocatePoint".to_owned(),
+                "point".to_owned(),
+            ));
+        }
+
+        let mut p_proj = MaybeUninit::uninit();
+        Ok(unsafe {
+            ptarray_locate_point(
+                (*p_line).points,
+                p.as_ptr(),
+                core::ptr::null_mut(),
+                p_proj.as_mut_ptr(),
+            )
+        })
+    }
+
+    /// Minimum planar distance. Empty inputs have no distance and return
+    /// `EmptyGeometryError`.
+    pub fn distance(&self, other: &LWGeom) -> Result<f64> {
+        self.check_distance_args(other, "ST_Distance")?;
+        Ok(unsafe { lwgeom_mindistance2d(self.as_ptr(), other.as_ptr()) })
+    }
+
+    /// Largest planar distance between a vertex of one geometry and the
+    /// other geometry.
+    pub fn max_distance(&self, other: &LWGeom) -> Result<f64> {
+        self.check_distance_args(other, "ST_MaxDistance")?;
+        Ok(unsafe { lwgeom_maxdistance2d(self.as_ptr(), other.as_ptr()) })
+    }
+
+    /// Minimum 3D distance. As in PostGIS, a missing Z on either side is taken
+    /// as "any value", which gives the 2D distance.
+    pub fn distance_3d(&self, other: &LWGeom) -> Result<f64> {
+        self.check_distance_args(other, "ST_3DDistance")?;
+        Ok(unsafe { lwgeom_mindistance3d(self.as_ptr(), other.as_ptr()) })
+    }
+
+    /// Whether the geometries are within `dist` of each other. The search
+    /// stops at the first pair of elements found within range. Empty inputs
+    /// are never within any distance.
+    pub fn dwithin(&self, other: &LWGeom, dist: f64) -> Result<bool> {
+        self.check_same_srid(other, "ST_DWithin")?;
+        if dist < 0.0 {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_DWithin".to_owned(),
+                "dist".to_owned(),
+            ));
+        }
+        if self.is_empty() || other.is_empty() {
+            return Ok(false);
+        }
+
+        let distance =
+            unsafe { lwgeom_mindistance2d_tolerance(self.as_ptr(), other.as_ptr(), dist) };
+        Ok(distance <= dist)
+    }
+
+    /// Whether every point of this geometry is within `dist` of `other`, i.e.
+    /// whether `other` buffered by `dist` covers it. The test is directed, so
+    /// a small geometry can be fully within `dist` of a large one but not the
+    /// other way round. Round parts of the buffer are approximated with the
+    /// default `quad_segs`, which can exclude points right at `dist`.
+    pub fn dfullywithin(&self, other: &LWGeom, dist: f64) -> Result<bool> {
+        self.check_same_srid(other, "ST_DFullyWithin")?;
+        if dist < 0.0 {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_DFullyWithin".to_owned(),
+                "dist".to_owned(),
+            ));
+        }
+        if self.is_empty() || other.is_empty() {
+            return Ok(false);
+        }
+
+        let _geos = geos::lock();
+        let g1 = GEOSGeom::from_lwgeom(self, "ST_DFullyWithin")?;
+        let g2 = GEOSGeom::from_lwgeom(other, "ST_DFullyWithin")?;
+        let buffered = GEOSGeom::from_ptr(
+            unsafe { GEOSBuffer(g2.as_ptr(), dist, BufferParams::default().quad_segs) },
+            "ST_DFullyWithin",
+        )?;
+        match unsafe { GEOSCovers(buffered.as_ptr(), g1.as_ptr()) } {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(geos::error("ST_DFullyWithin")),
+        }
+    }
+
+    fn check_distance_args(&self, other: &LWGeom, function: &str) -> Result<()> {
+        self.check_same_srid(other, function)?;
+        if self.is_empty() || other.is_empty() {
+            return Err(LWGeomError::EmptyGeometryError(function.to_owned()));
+        }
+        Ok(())
+    }
+
+    fn check_same_srid(&self, other: &LWGeom, function: &str) -> Result<()> {
+        let (srid1, srid2) = unsafe {
+            (
+                lwgeom_get_srid(self.as_ptr()),
+                lwgeom_get_srid(other.as_ptr()),
+            )
+        };
+        if srid1 != srid2 {
+            return Err(LWGeomError::SRIDMismatchError(
+                function.to_owned(),
+                srid1,
+                srid2,
+            ));
+        }
+        Ok(())
+    }
+
+    /// The 2D point on `self` nearest to `other`.
+    pub fn closest_point(&self, other: &LWGeom) -> Result<Self> {
+        self.check_distance_args(other, "ST_ClosestPoint")?;
+        lwerror::init();
+        let p_geom = unsafe { lwgeom_closest_point(self.as_ptr(), other.as_ptr()) };
+        if p_geom.is_null() {
+            return Err(lwerror::error(LWGeomError::NullPtrError));
+        }
+
+        Ok(Self::from_ptr(p_geom))
+    }
+
+    pub fn shortest_line(&self, other: &LWGeom) -> Result<Self> {
+        self.check_distance_args(other, "ST_ShortestLine")?;
+        lwerror::init();
+        let p_geom = unsafe { lwgeom_closest_line(self.as_ptr(), other.as_ptr()) };
+        if p_geom.is_null() {
+            return Err(lwerror::error(LWGeomError::NullPtrError));
+        }
+
+        Ok(Self::from_ptr(p_geom))
+    }
+
+    /// The 2D line between the two points of `self` and `other` furthest
+    /// apart; with `other` equal to `self` this is the geometry's diameter.
+    pub fn longest_line(&self, other: &LWGeom) -> Result<Self> {
+        self.check_distance_args(other, "ST_LongestLine")?;
+        lwerror::init();
+        let p_geom = unsafe { lwgeom_furthest_line(self.as_ptr(), other.as_ptr()) };
+        if p_geom.is_null() {
+            return Err(lwerror::error(LWGeomError::NullPtrError));
+        }
+
+        Ok(Self::from_ptr(p_geom))
+    }
+
+    pub fn intersects(&self, other: &LWGeom) -> Result<bool> {
+        self.check_same_srid(other, "ST_Intersects")?;
+        geos::predicate(self, other, "ST_Intersects", GEOSIntersects)
+    }
+
+    pub fn contains(&self, other: &LWGeom) -> Result<bool> {
+        self.check_same_srid(other, "ST_Contains")?;
+        geos::predicate(self, other, "ST_Contains", GEOSContains)
+    }
+
+    pub fn within(&self, other: &LWGeom) -> Result<bool> {
+        self.check_same_srid(other, "ST_Within")?;
+        geos::predicate(self, other, "ST_Within", GEOSWithin)
+    }
+
+    pub fn covers(&self, other: &LWGeom) -> Result<bool> {
+        self.check_same_srid(other, "ST_Covers")?;
+        geos::predicate(self, other, "ST_Covers", GEOSCovers)
+    }
+
+    pub fn covered_by(&self, other: &LWGeom) -> Result<bool> {
+        self.check_same_srid(other, "ST_CoveredBy")?;
+        geos::predicate(self, other, "ST_CoveredBy", GEOSCoveredBy)
+    }
+
+    pub fn touches(&self, other: &LWGeom) -> Result<bool> {
+        self.check_same_srid(other, "ST_Touches")?;
+        geos::predicate(self, other, "ST_Touches", GEOSTouches)
+    }
+
+    pub fn crosses(&self, other: &LWGeom) -> Result<bool> {
+        self.check_same_srid(other, "ST_Crosses")?;
+        geos::predicate(self, other, "ST_Crosses", GEOSCrosses)
+    }
+
+    pub fn overlaps(&self, other: &LWGeom) -> Result<bool> {
+        self.check_same_srid(other, "ST_Overlaps")?;
+        geos::predicate(self, other, "ST_Overlaps", GEOSOverlaps)
+    }
+
+    pub fn disjoint(&self, other: &LWGeom) -> Result<bool> {
+        self.check_same_srid(other, "ST_Disjoint")?;
+        geos::predicate(self, other, "ST_Disjoint", GEOSDisjoint)
+    }
+
+    /// Spatial equality, ignoring vertex order and start points, unlike the
+    /// structural `==`.
+    pub fn equals(&self, other: &LWGeom) -> Result<bool> {
+        self.check_same_srid(other, "ST_Equals")?;
+        geos::predicate(self, other, "ST_Equals", GEOSEquals)
+    }
+
+    /// The DE-9IM intersection matrix, e.g. `212101212`.
+    pub fn relate(&self, other: &LWGeom) -> Result<String> {
+        self.check_same_srid(other, "ST_Relate")?;
+        let _geos = geos::lock();
+        let g1 = GEOSGeom::from_lwgeom(self, "ST_Relate")?;
+        let g2 = GEOSGeom::from_lwgeom(other, "ST_Relate")?;
+        let p_matrix = unsafe { GEOSRelate(g1.as_ptr(), g2.as_ptr()) };
+        if p_matrix.is_null() {
+            return Err(geos::error("ST_Relate"));
+        }
+
+        let matrix = unsafe { CStr::from_ptr(p_matrix) }
+            .to_string_lossy()
+            .into_owned();
+        unsafe { GEOSFree(p_matrix.cast()) };
+        Ok(matrix)
+    }
+
+    /// Tests the DE-9IM matrix against a 9 character mask such as `T*F**F***`.
+    pub fn relate_pattern(&self, other: &LWGeom, pattern: &str) -> Result<bool> {
+        if pattern.chars().count() != 9 {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_Relate".to_owned(),
+                "pattern".to_owned(),
+            ));
+        }
+
+        self.check_same_srid(other, "ST_Relate")?;
+        let c_pattern = CString::new(pattern)?;
+        let _geos = geos::lock();
+        let g1 = GEOSGeom::from_lwgeom(self, "ST_Relate")?;
+        let g2 = GEOSGeom::from_lwgeom(other, "ST_Relate")?;
+        match unsafe { GEOSRelatePattern(g1.as_ptr(), g2.as_ptr(), c_pattern.as_ptr()) } {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(geos::error("ST_Relate")),
+        }
+    }
+
+    pub fn hausdorff_distance(&self, other: &LWGeom) -> Result<f64> {
+        self.check_same_srid(other, "ST_HausdorffDistance")?;
+        let _geos = geos::lock();
+        let g1 = GEOSGeom::from_lwgeom(self, "ST_HausdorffDistance")?;
+        let g2 = GEOSGeom::from_lwgeom(other, "ST_HausdorffDistance")?;
+        let mut distance = 0.0;
+        if unsafe { GEOSHausdorffDistance(g1.as_ptr(), g2.as_ptr(), &mut distance) } == 0 {
+            return Err(geos::error("ST_HausdorffDistance"));
+        }
+
+        Ok(distance)
+    }
+
+    /// Densifies every segment into pieces of `densify_frac` times its length
+    /// before measuring, which gets closer to the true Hausdorff distance for
+    /// geometries with long segments.
+    pub fn hausdorff_distance_densify(&self, other: &LWGeom, densify_frac: f64) -> Result<f64> {
+        if densify_frac <= 0.0 || densify_frac > 1.0 {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_HausdorffDistance".to_owned(),
+                "densify_frac".to_owned(),
+            ));
+        }
+
+        self.check_same_srid(other, "ST_HausdorffDistance")?;
+        let _geos = geos::lock();
+        let g1 = GEOSGeom::from_lwgeom(self, "ST_HausdorffDistance")?;
+        let g2 = GEOSGeom::from_lwgeom(other, "ST_HausdorffDistance")?;
+        let mut distance = 0.0;
+        if unsafe {
+            GEOSHausdorffDistanceDensify(g1.as_ptr(), g2.as_ptr(), densify_frac, &mut distance)
+        } == 0
+        {
+            return Err(geos::error("ST_HausdorffDistance"));
+        }
+
+        Ok(distance)
+    }
+
+    /// `densify` splits every segment into pieces of that fraction of its
+    /// length first, refining the discrete estimate at the cost of speed.
+    pub fn frechet_distance(&self, other: &LWGeom, densify: Option<f64>) -> Result<f64> {
+        if let Some(densify_frac) = densify {
+            if densify_frac <= 0.0 || densify_frac > 1.0 {
+                return Err(LWGeomError::InvalidParameterError(
+                    "ST_FrechetDistance".to_owned(),
+                    "densify_frac".to_owned(),
+                ));
+            }
+        }
+
+        self.check_same_srid(other, "ST_FrechetDistance")?;
+        let _geos = geos::lock();
+        let g1 = GEOSGeom::from_lwgeom(self, "ST_FrechetDistance")?;
+        let g2 = GEOSGeom::from_lwgeom(other, "ST_FrechetDistance")?;
+        let mut distance = 0.0;
+        let result = match densify {
+            Some(densify_frac) => unsafe {
+                GEOSFrechetDistanceDensify(g1.as_ptr(), g2.as_ptr(), densify_frac, &mut distance)
+            },
+            None => unsafe { GEOSFrechetDistance(g1.as_ptr(), g2.as_ptr(), &mut distance) },
+        };
+        if result == 0 {
+            return Err(geos::error("ST_FrechetDistance"));
+        }
+
+        Ok(distance)
+    }
+
+    pub fn minimum_bounding_circle(&self, segs_per_quarter: u32) -> Result<Self> {
+        let srid = unsafe { lwgeom_get_srid(self.as_ptr()) };
+        if self.is_empty() {
+            let p_poly = unsafe { lwpoly_construct_empty(srid, 0, 0) };
+            return Ok(LWPoly::from_ptr(p_poly).into_lwgeom());
+        }
+        if segs_per_quarter == 0 {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_MinimumBoundingCircle".to_owned(),
+                "segs_per_quarter".to_owned(),
+            ));
+        }
+
+        let (x, y, radius) = self.calculate_mbc("ST_MinimumBoundingCircle")?;
+        if radius == 0.0 {
+            return Ok(Self::from_ptr(unsafe {
+                lwpoint_as_lwgeom(lwpoint_make2d(srid, x, y))
+            }));
+        }
+
+        let p_poly = unsafe {
+            lwpoly_construct_circle(srid, x, y, radius, segs_per_quarter, LW_TRUE as c_char)
+        };
+        Ok(LWPoly::from_ptr(p_poly).into_lwgeom())
+    }
+
+    /// Returns the center point and radius of the minimum bounding circle.
+    pub fn minimum_bounding_radius(&self) -> Result<(Self, f64)> {
+        let (x, y, radius) = self.calculate_mbc("ST_MinimumBoundingRadius")?;
+        let srid = unsafe { lwgeom_get_srid(self.as_ptr()) };
+        let center = Self::from_ptr(unsafe { lwpoint_as_lwgeom(lwpoint_make2d(srid, x, y)) });
+        Ok((center, radius))
+    }
+
+    fn calculate_mbc(&self, function: &str) -> Result<(f64, f64, f64)> {
+        lwerror::init();
+        let p_mbc = unsafe { lwgeom_calculate_mbc(self.as_ptr()) };
+        if p_mbc.is_null() {
+            return Err(lwerror::error(LWGeomError::CalculateError(
+                function.to_owned(),
+            )));
+        }
+
+        let mbc = unsafe { ((*(*p_mbc).center).x, (*(*p_mbc).center).y, (*p_mbc).radius) };
+        unsafe { lwboundingcircle_destroy(p_mbc) };
+        Ok(mbc)
+    }
+
+    /// Returns the bounding box as a Polygon, or as a LineString or Point
+    /// when the box collapses to a segment or a single coordinate.
+    pub fn envelope(&self) -> Result<Self> {
+        let srid = unsafe { lwgeom_get_srid(self.as_ptr()) };
+        if self.is_empty() {
+            return self.empty_like();
+        }
+
+        let bbox = self.get_bbox_ref();
+        let (xmin, ymin, xmax, ymax) = (bbox.xmin(), bbox.ymin(), bbox.xmax(), bbox.ymax());
+        if xmin == xmax && ymin == ymax {
+            return Ok(Self::from_ptr(unsafe {
+                lwpoint_as_lwgeom(lwpoint_make2d(srid, xmin, ymin))
+            }));
+        }
+
+        if xmin == xmax || ymin == ymax {
+            let p_points = unsafe { ptarray_construct_empty(0, 0, 2) };
+            for (x, y) in [(xmin, ymin), (xmax, ymax)] {
+                let point = POINT4D {
+                    x,
+                    y,
+                    z: 0.0,
+                    m: 0.0,
+                };
+                unsafe { ptarray_append_point(p_points, &point, LW_TRUE as c_int) };
+            }
+            return Ok(Self::from_ptr(unsafe {
+                lwline_as_lwgeom(lwline_construct(srid, core::ptr::null_mut(), p_points))
+            }));
+        }
+
+        Ok(LWPoly::construct_envelope(srid, xmin, ymin, xmax, ymax).into_lwgeom())
+    }
+
+    /// Empty geometry with the type, SRID and dimensions of `self`.
+    fn empty_like(&self) -> Result<Self> {
+        lwerror::init();
+        let p_geom = unsafe {
+            lwgeom_construct_empty(
+                (*self.as_ptr()).type_,
+                lwgeom_get_srid(self.as_ptr()),
+                self.has_z() as c_char,
+                self.has_m() as c_char,
+            )
+        };
+        if p_geom.is_null() {
+            return Err(lwerror::error(LWGeomError::NullPtrError));
+        }
+
+        Ok(Self::from_ptr(p_geom))
+    }
+
+    /// Canonical form, `ST_Normalize`: rings start at a deterministic vertex
+    /// with consistent orientation and collection members are sorted, so
+    /// geometrically identical inputs normalize to the same output.
+    pub fn normalize(&self) -> Result<Self> {
+        let _geos = geos::lock();
+        let g = GEOSGeom::from_lwgeom(self, "ST_Normalize")?;
+        if unsafe { GEOSNormalize(g.as_ptr()) } != 0 {
+            return Err(geos::error("ST_Normalize"));
+        }
+
+        g.to_lwgeom(
+            unsafe { lwgeom_get_srid(self.as_ptr()) },
+            self.has_z(),
+            "ST_Normalize",
+        )
+    }
+
+    pub fn normalize_in_place(&mut self) -> Result<()> {
+        *self = self.normalize()?;
+        Ok(())
+    }
+
+    pub fn oriented_envelope(&self) -> Result<Self> {
+        let _geos = geos::lock();
+        let g = GEOSGeom::from_lwgeom(self, "ST_OrientedEnvelope")?;
+        let result = GEOSGeom::from_ptr(
+            unsafe { GEOSMinimumRotatedRectangle(g.as_ptr()) },
+            "ST_OrientedEnvelope",
+        )?;
+        result.to_lwgeom(
+            unsafe { lwgeom_get_srid(self.as_ptr()) },
+            self.has_z(),
+            "ST_OrientedEnvelope",
+        )
+    }
+
+    pub fn boundary(&self) -> Result<Self> {
+        lwerror::init();
+        let p_geom = unsafe { lwgeom_boundary(self.as_ptr()) };
+        if p_geom.is_null() {
+            return Err(lwerror::error(LWGeomError::CalculateError(
+                "ST_Boundary".to_owned(),
+            )));
+        }
+
+        let mut geom = Self::from_ptr(p_geom);
+        geom.set_srid(unsafe { lwgeom_get_srid(self.as_ptr()) });
+        Ok(geom)
+    }
+
+    /// Reverses the vertex order of lines and of every polygon ring.
+    pub fn reverse(&self) -> Self {
+        let mut geom = self.clone();
+        geom.reverse_in_place();
+        geom
+    }
+
+    pub fn reverse_in_place(&mut self) {
+        unsafe { lwgeom_reverse_in_place(self.as_ptr()) }
+    }
+
+    /// Orients exterior rings clockwise and interior rings counterclockwise,
+    /// `ST_ForcePolygonCW`.
+    pub fn force_cw(&self) -> Self {
+        let geom = self.clone();
+        unsafe { lwgeom_force_clockwise(geom.as_ptr()) };
+        geom
+    }
+
+    /// Orients exterior rings counterclockwise and interior rings clockwise,
+    /// `ST_ForcePolygonCCW`.
+    pub fn force_ccw(&self) -> Self {
+        let mut geom = self.force_cw();
+        geom.reverse_in_place();
+        geom
+    }
+
+    /// True when every exterior ring is clockwise and every interior ring
+    /// counterclockwise. Geometries without polygons count as oriented.
+    pub fn is_polygon_cw(&self) -> bool {
+        unsafe { lwgeom_is_clockwise(self.as_ptr()) != 0 }
+    }
+
+    pub fn is_polygon_ccw(&self) -> bool {
+        self.reverse().is_polygon_cw()
+    }
+
+    pub fn force_2d(&self) -> Self {
+        Self::from_ptr(unsafe { lwgeom_force_2d(self.as_ptr()) })
+    }
+
+    /// Adds a Z ordinate set to `z` where missing; existing Z values are kept
+    /// and M is dropped.
+    pub fn force_3dz(&self, z: f64) -> Self {
+        Self::from_ptr(unsafe { lwgeom_force_3dz(self.as_ptr(), z) })
+    }
+
+    /// Adds an M ordinate set to `m` where missing; existing M values are kept
+    /// and Z is dropped.
+    pub fn force_3dm(&self, m: f64) -> Self {
+        Self::from_ptr(unsafe { lwgeom_force_3dm(self.as_ptr(), m) })
+    }
+
+    pub fn force_4d(&self, z: f64, m: f64) -> Self {
+        Self::from_ptr(unsafe { lwgeom_force_4d(self.as_ptr(), z, m) })
+    }
+
+    /// Wraps the geometry in a GeometryCollection; collections and multi
+    /// geometries are relabelled as GeometryCollection.
+    pub fn force_collection(&self) -> Self {
+        if self.is_empty() {
+            let p_geom = unsafe {
+                lwgeom_construct_empty(
+                    COLLECTIONTYPE as u8,
+                    lwgeom_get_srid(self.as_ptr()),
+                    self.has_z() as c_char,
+                    self.has_m() as c_char,
+                )
+            };
+            return Self::from_ptr(p_geom);
+        }
+
+        let geom = self.clone();
+        if unsafe { lwgeom_is_collection(geom.as_ptr()) } != 0 {
+            unsafe { (*geom.as_ptr()).type_ = COLLECTIONTYPE as u8 };
+            return geom;
+        }
+
+        let srid = unsafe { lwgeom_get_srid(geom.as_ptr()) };
+        let p_geoms = unsafe { lwalloc(core::mem::size_of::<*mut LWGEOM>()) }.cast::<*mut LWGEOM>();
+        unsafe { *p_geoms = geom.into_ptr() };
+        let p_collection = unsafe {
+            lwcollection_construct(
+                COLLECTIONTYPE as u8,
+                srid,
+                core::ptr::null_mut(),
+                1,
+                p_geoms,
+            )
+        };
+        Self::from_ptr(unsafe { lwcollection_as_lwgeom(p_collection) })
+    }
+
+    /// Promotes Point, LineString and Polygon to their multi types, `ST_Multi`.
+    /// Multi geometries and collections are returned unchanged.
+    pub fn force_multi(&self) -> Self {
+        // lwgeom_as_multi makes a shallow copy that borrows our coordinates.
+        let p_multi = unsafe { lwgeom_as_multi(self.as_ptr()) };
+        let p_geom = unsafe { lwgeom_clone_deep(p_multi) };
+        unsafe { lwgeom_free(p_multi) };
+        Self::from_ptr(p_geom)
+    }
+
+    /// Rewrites the geometry using only types known to `version`,
+    /// `ST_ForceSFS`. Curves are stroked with 32 segments per quarter
+    /// circle.
+    pub fn force_sfs(&self, version: SfsVersion) -> Result<Self> {
+        lwerror::init();
+        // lwgeom_force_sfs strokes curves without freeing its input, so
+        // linearize up front and hand it a geometry it can consume.
+        let geom = if unsafe { lwgeom_has_arc(self.as_ptr()) } != 0 {
+            let p_geom = unsafe { lwgeom_stroke(self.as_ptr(), 32) };
+            if p_geom.is_null() {
+                return Err(lwerror::error(LWGeomError::NullPtrError));
+            }
+            Self::from_ptr(p_geom)
+        } else {
+            self.clone()
+        };
+
+        let p_geom = unsafe { lwgeom_force_sfs(geom.into_ptr(), version as c_int) };
+        if p_geom.is_null() {
+            return Err(lwerror::error(LWGeomError::NullPtrError));
+        }
+
+        Ok(Self::from_ptr(p_geom))
+    }
+
+    /// Applies `matrix`, `ST_Affine`. Geometries without Z only use the
+    /// `a, b, d, e, xoff, yoff` terms; the Z row and column are ignored.
+    pub fn affine(&self, matrix: &AffineMatrix) -> Self {
+        let mut geom = self.clone();
+        geom.affine_in_place(matrix);
+        geom
+    }
+
+    pub fn affine_in_place(&mut self, matrix: &AffineMatrix) {
+        let affine = matrix.to_affine();
+        unsafe {
+            lwgeom_affine(self.as_ptr(), &affine);
+            lwgeom_refresh_bbox(self.as_ptr());
+        }
+    }
+
+    pub fn translate(&self, dx: f64, dy: f64) -> Self {
+        self.translate_3d(dx, dy, 0.0)
+    }
+
+    pub fn translate_3d(&self, dx: f64, dy: f64, dz: f64) -> Self {
+        self.affine(&AffineMatrix::translation(dx, dy, dz))
+    }
+
+    pub fn scale(&self, sx: f64, sy: f64) -> Self {
+        self.affine(&AffineMatrix::scaling(sx, sy, 1.0))
+    }
+
+    /// Rotates counterclockwise by `radians` about the origin, `ST_Rotate`.
+    pub fn rotate(&self, radians: f64) -> Self {
+        self.affine(&AffineMatrix::rotation_z(radians))
+    }
+
+    /// Rotates counterclockwise by `radians` about `(origin_x, origin_y)`.
+    pub fn rotate_around(&self, radians: f64, origin_x: f64, origin_y: f64) -> Self {
+        let matrix = AffineMatrix::translation(-origin_x, -origin_y, 0.0)
+            .then(&AffineMatrix::rotation_z(radians))
+            .then(&AffineMatrix::translation(origin_x, origin_y, 0.0));
+        self.affine(&matrix)
+    }
+
+    /// Like `rotate_around`, with the origin given as a non-empty point.
+    pub fn rotate_around_point(&self, radians: f64, origin: &LWGeom) -> Result<Self> {
+        let (Some(x), Some(y)) = origin.as_point().map_or((None, None), |p| (p.x(), p.y())) else {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_Rotate".to_owned(),
+                "origin".to_owned(),
+            ));
+        };
+
+        Ok(self.rotate_around(radians, x, y))
+    }
+
+    pub fn rotate_around_centroid(&self, radians: f64) -> Result<Self> {
+        self.rotate_around_point(radians, &self.centroid()?)
+    }
+
+    pub fn flip_coordinates(&self) -> Self {
+        let mut geom = self.clone();
+        geom.flip_coordinates_in_place();
+        geom
+    }
+
+    /// Exchanges two ordinates throughout the geometry, `ST_SwapOrdinates`.
+    /// Both must be present, so Z or M cannot be swapped on a 2D geometry.
+    pub fn swap_ordinates(&self, a: Ordinate, b: Ordinate) -> Result<Self> {
+        for (ordinate, param) in [(a, "a"), (b, "b")] {
+            let present = match ordinate {
+                Ordinate::X | Ordinate::Y => true,
+                Ordinate::Z => self.has_z(),
+                Ordinate::M => self.has_m(),
+            };
+            if !present {
+                return Err(LWGeomError::InvalidParameterError(
+                    "ST_SwapOrdinates".to_owned(),
+                    param.to_owned(),
+                ));
+            }
+        }
+
+        let geom = self.clone();
+        unsafe {
+            lwgeom_swap_ordinates(geom.as_ptr(), a.to_lword(), b.to_lword());
+            // The bbox caches Z and M extents as well, so refresh it for any pair.
+            lwgeom_refresh_bbox(geom.as_ptr());
+        }
+        Ok(geom)
+    }
+
+    /// Swaps X and Y, leaving Z and M untouched, `ST_FlipCoordinates`.
+    pub fn flip_coordinates_in_place(&mut self) {
+        unsafe {
+            lwgeom_swap_ordinates(self.as_ptr(), LWORD_T_LWORD_X, LWORD_T_LWORD_Y);
+            lwgeom_refresh_bbox(self.as_ptr());
+        }
+    }
+
+    pub fn centroid(&self) -> Result<Self> {
+        lwerror::init();
+        let p_geom = unsafe { lwgeom_centroid(self.as_ptr()) };
+        if p_geom.is_null() {
+            return Err(lwerror::error(LWGeomError::CalculateError(
+                "ST_Centroid".to_owned(),
+            )));
+        }
+
+        let mut geom = Self::from_ptr(p_geom);
+        geom.set_srid(unsafe { lwgeom_get_srid(self.as_ptr()) });
+        Ok(geom)
+    }
+
+    /// Reprojects from the geometry's SRID to `target_srid`, both read as
+    /// EPSG codes.
+    pub fn transform(&self, target_srid: i32) -> Result<Self> {
+        let Some(srid) = self.get_srid() else {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_Transform".to_owned(),
+                "geom".to_owned(),
+            ));
+        };
+
+        let c_source = CString::new(format!("EPSG:{srid}"))?;
+        let c_target = CString::new(format!("EPSG:{target_srid}"))?;
+        let mut geom = self.clone();
+        lwerror::init();
+        let result = unsafe {
+            lwgeom_transform_from_str(geom.as_ptr(), c_source.as_ptr(), c_target.as_ptr())
+        };
+        if result == LW_FAILURE as c_int {
+            return Err(lwerror::error(LWGeomError::CalculateError(
+                "ST_Transform".to_owned(),
+            )));
+        }
+
+        unsafe { lwgeom_refresh_bbox(geom.as_ptr()) };
+        geom.set_srid(target_srid);
+        Ok(geom)
+    }
+
+    pub fn get_bbox_ref(&self) -> &GBoxRef {
+        let p_bbox = unsafe { lwgeom_get_bbox(self.as_ptr()) };
+        GBoxRef::from_ptr(p_bbox.cast_mut())
+    }
+
+    pub fn tile_envelope(
+        zoom: i32, x: i32, y: i32, bounds: Option<&LWGeom>, margin: Option<f64>,
+    ) -> Result<Self> {
+        let bounds2 =   Self::from_ewkt("SRID=3857;LINESTRING(-20037508.342789 -20037508.342789,20037508.342789 20037508.342789)").unwrap() ;
+        let bounds = match bounds {
+            Some(bounds) => bounds,
+            None => &bounds2,
+        };
+        let bbox = bounds.get_bbox_ref();
+
+        let srid = bounds.get_srid().unwrap_or(3857);
+
+        let margin = margin.unwrap_or(0.0);
+        if margin < -0.5 {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_TileEnvelope".to_owned(),
+                "margin".to_owned(),
+            ));
+        }
+
+        let bounds_width = bbox.width();
+        let bounds_height = bbox.height();
+        if bounds_width <= 0.0 || bounds_height <= 0.0 {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_TileEnvelope".to_owned(),
+                "bounds".to_owned(),
+            ));
+        }
+
+        if !(0..32).contains(&zoom) {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_TileEnvelope".to_owned(),
+                "zoom".to_owned(),
+            ));
+        }
+
+        let world_tile_size = 1 << zoom.min(31);
+        if x < 0 || x >= world_tile_size {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_TileEnvelope".to_owned(),
+                "x".to_owned(),
+            ));
+        }
+        if y < 0 || y >= world_tile_size {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_TileEnvelope".to_owned(),
+                "y".to_owned(),
+            ));
+        }
+
+        let tile_geo_size_x = bounds_width / world_tile_size as f64;
+        let tile_geo_size_y = bounds_height / world_tile_size as f64;
+
+        let (x1, x2) = if (1.0 + margin * 2.0) > world_tile_size as f64 {
+            (bbox.xmin(), bbox.xmax())
+        } else {
+            (
+                bbox.xmin() + tile_geo_size_x * (x as f64 - margin),
+                bbox.xmin() + tile_geo_size_x * (x as f64 + 1.0 + margin),
+            )
+        };
+        let mut y1 = bbox.ymax() - tile_geo_size_y * (y as f64 + 1.0 + margin);
+        let mut y2 = bbox.ymax() - tile_geo_size_y * (y as f64 - margin);
+        if y1 < bbox.ymin() {
+            y1 = bbox.ymin()
+        }
+        if y2 > bbox.ymax() {
+            y2 = bbox.ymax()
+        }
+
+        Ok(LWPoly::construct_envelope(srid, x1, y1, x2, y2).into_lwgeom())
+    }
+
+    /// Converts to Mapbox Vector Tile coordinates for a tile covering
+    /// `tile_bounds`, `ST_AsMVTGeom`: `extent` units per side with Y pointing
+    /// down, optionally clipped to the tile grown by `buffer` units. Returns
+    /// `None` when nothing of the geometry survives.
+    pub fn as_mvt_geom(
+        &self, tile_bounds: &GBoxRef, extent: u32, buffer: u32, clip: bool,
+    ) -> Result<Option<Self>> {
+        if extent == 0 {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_AsMVTGeom".to_owned(),
+                "extent".to_owned(),
+            ));
+        }
+        let (width, height) = (tile_bounds.width(), tile_bounds.height());
+        if width <= 0.0 || height <= 0.0 {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_AsMVTGeom".to_owned(),
+                "tile_bounds".to_owned(),
+            ));
+        }
+
+        let basic_type = basic_type(self.as_ptr());
+        if self.is_empty() || basic_type == 0 {
+            return Ok(None);
+        }
+
+        // Drop detail below half a tile pixel before doing any real work.
+        let (res_x, res_y) = (width / extent as f64, height / extent as f64);
+        let res = res_x.min(res_y) / 2.0;
+        let mut geom = self.clone();
+        geom.remove_repeated_points_in_place(res)?;
+        let mut geom = geom.simplify(res, false)?;
+        if geom.is_empty() {
+            return Ok(None);
+        }
+
+        if clip {
+            // Just under half a pixel extra keeps points that only round onto
+            // the tile edge, without pulling in the next pixel.
+            let margin = res * (1.0 - f32::EPSILON as f64) + res_x * buffer as f64;
+            let bounds = tile_bounds.expand(margin);
+            geom = geom.clip_by_rect(bounds.xmin(), bounds.ymin(), bounds.xmax(), bounds.ymax())?;
+            match geom.extract_basic_type(basic_type) {
+                Some(extracted) => geom = extracted,
+                None => return Ok(None),
+            }
+        }
+
+        let (fx, fy) = (extent as f64 / width, -(extent as f64 / height));
+        geom.affine_in_place(&AffineMatrix {
+            a: fx,
+            e: fy,
+            xoff: -tile_bounds.xmin() * fx,
+            yoff: -tile_bounds.ymax() * fy,
+            ..AffineMatrix::identity()
+        });
+        let mut geom = geom.snap_to_grid(0.0, 0.0, 1.0, 1.0)?;
+        if basic_type == POLYGONTYPE && !geom.is_empty() {
+            // Snapping can fold rings onto themselves; repair them and let the
+            // collapsed parts fall out as lines below.
+            geom = geom.make_valid_geos("ST_AsMVTGeom")?;
+        }
+        let Some(mut geom) = geom.extract_basic_type(basic_type) else {
+            return Ok(None);
+        };
+
+        // Flipping Y swaps the winding; MVT wants exteriors clockwise on screen.
+        if basic_type == POLYGONTYPE {
+            geom = geom.force_ccw();
+        }
+        geom.set_srid(unsafe { lwgeom_get_srid(self.as_ptr()) });
+        Ok(Some(geom))
+    }
+
+    fn make_valid_geos(&self, function: &str) -> Result<Self> {
+        let _geos = geos::lock();
+        let g = GEOSGeom::from_lwgeom(self, function)?;
+        let result = GEOSGeom::from_ptr(unsafe { GEOSMakeValid(g.as_ptr()) }, function)?;
+        result.to_lwgeom(
+            unsafe { lwgeom_get_srid(self.as_ptr()) },
+            self.has_z(),
+            function,
+        )
+    }
+
+    /// Keeps only the non-empty parts of dimension `basic_type`, merged into
+    /// a single or multi geometry.
+    fn extract_basic_type(&self, wanted: u32) -> Option<Self> {
+        if self.is_empty() {
+            return None;
+        }
+        let geom_type = unsafe { (*self.as_ptr()).type_ };
+        if geom_type as u32 == wanted
+            || geom_type == unsafe { lwtype_get_collectiontype(wanted as u8) }
+        {
+            return Some(self.clone());
+        }
+
+        fn collect_parts(geom: &LWGeomRef, wanted: u32, parts: &mut Vec<LWGeom>) {
+            let geom_type = unsafe { (*geom.as_ptr()).type_ } as u32;
+            if geom_type == wanted {
+                if !geom.is_empty() {
+                    parts.push(LWGeom::from_ptr(unsafe {
+                        lwgeom_clone_deep(geom.as_ptr())
+                    }));
+                }
+            } else if unsafe { lwgeom_is_collection(geom.as_ptr()) } != 0 {
+                for part in geom {
+                    collect_parts(part, wanted, parts);
+                }
+            }
+        }
+
+        let mut parts = Vec::new();
+        collect_parts(LWGeomRef::from_ptr(self.as_ptr()), wanted, &mut parts);
+        let srid = unsafe { lwgeom_get_srid(self.as_ptr()) };
+        for part in &mut parts {
+            part.set_srid(srid);
+        }
+        match parts.len() {
+            0 => None,
+            1 => parts.pop(),
+            _ => LWGeom::collect(&parts).ok(),
+        }
+    }
+}
+
+impl LWGeomRef {
+    pub fn as_text(&self, precision: Option<i32>) -> Result<String> {
+        to_wkt(self.as_ptr(), WKT_ISO as u8, precision)
+    }
+
+    pub fn as_ewkt(&self, precision: Option<i32>) -> Result<String> {
+        to_wkt(self.as_ptr(), WKT_EXTENDED as u8, precision)
+    }
+
+    pub fn as_point(&self) -> Option<&LWPointRef> {
+        let p_point = unsafe { lwgeom_as_lwpoint(self.as_ptr()) };
+        (!p_point.is_null()).then(|| LWPointRef::from_ptr(p_point))
+    }
+
+    pub fn as_line(&self) -> Option<&LWLineRef> {
+        let p_line = unsafe { lwgeom_as_lwline(self.as_ptr()) };
+        (!p_line.is_null()).then(|| LWLineRef::from_ptr(p_line))
+    }
+
+    pub fn as_polygon(&self) -> Option<&LWPolyRef> {
+        let p_poly = unsafe { lwgeom_as_lwpoly(self.as_ptr()) };
+        (!p_poly.is_null()).then(|| LWPolyRef::from_ptr(p_poly))
+    }
+
+    pub fn vertices(&self) -> VertexIter<'_> {
+        VertexIter::new(self)
+    }
+}
+
+impl LWGeomRef {
+    pub fn has_srid(&self) -> bool {
+        unsafe { lwgeom_has_srid(self.as_ptr()) != 0 }
+    }
+
+    pub fn get_srid(&self) -> Option<i32> {
+        if self.has_srid() {
+            Some(unsafe { lwgeom_get_srid(self.as_ptr()) })
+        } else {
+            None
+        }
+    }
+
+    pub fn set_srid(&mut self, srid: i32) {
+        unsafe { lwgeom_set_srid(self.as_ptr(), srid) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        unsafe { lwgeom_is_empty(self.as_ptr()) != 0 }
+    }
+
+    pub fn has_z(&self) -> bool {
+        unsafe { lwgeom_has_z(self.as_ptr()) != 0 }
+    }
+
+    pub fn has_m(&self) -> bool {
+        unsafe { lwgeom_has_m(self.as_ptr()) != 0 }
+    }
+
+    pub fn is_3d(&self) -> bool {
+        self.has_z()
+    }
+
+    pub fn is_measured(&self) -> bool {
+        self.has_m()
+    }
+
+    pub fn ndims(&self) -> u8 {
+        unsafe { lwgeom_ndims(self.as_ptr()) as u8 }
+    }
+
+    pub fn num_geometries(&self) -> usize {
+        let p_collection = unsafe { lwgeom_as_lwcollection(self.as_ptr()) };
+        if !p_collection.is_null() {
+            unsafe { (*p_collection).ngeoms as usize }
+        } else if self.is_empty() {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Returns the `n`th member of a collection, counting from 0. A non-empty
+    /// single geometry is its own only member.
+    pub fn geometry_n(&self, n: usize) -> Option<&LWGeomRef> {
+        if n >= self.num_geometries() {
+            return None;
+        }
+
+        let p_collection = unsafe { lwgeom_as_lwcollection(self.as_ptr()) };
+        if p_collection.is_null() {
+            Some(LWGeomRef::from_ptr(self.as_ptr()))
+        } else {
+            Some(LWGeomRef::from_ptr(unsafe {
+                *(*p_collection).geoms.add(n)
+            }))
+        }
+    }
+
+    pub fn geometries(&self) -> GeometryIter<'_> {
+        self.into_iter()
+    }
+
+    pub fn mem_size(&self) -> usize {
+        mem_size(self.as_ptr())
+    }
+
+    pub fn get_bbox_ref(&self) -> &GBoxRef {
+        let p_bbox = unsafe { lwgeom_get_bbox(self.as_ptr()) };
+        GBoxRef::from_ptr(p_bbox.cast_mut())
+    }
+}
+
+/// `ST_Angle`: with three points the clockwise angle at `p2` from `p1` to
+/// `p3`, with four the angle between the vectors `p1->p2` and `p3->p4`.
+/// The result is in radians within `[0, 2π)`.
+pub fn angle(p1: &LWGeom, p2: &LWGeom, p3: &LWGeom, p4: Option<&LWGeom>) -> Result<f64> {
+    let point = |geom: &LWGeom, name: &str| {
+        p1.check_same_srid(geom, "ST_Angle")?;
+        let point = geom.as_point().and_then(|point| {
+            Some(POINT2D {
+                x: point.x()?,
+                y: point.y()?,
+            })
+        });
+        point.ok_or_else(|| {
+            LWGeomError::InvalidParameterError("ST_Angle".to_owned(), name.to_owned())
+        })
+    };
+    let azimuth = |(from, from_name): (POINT2D, &str), (to, to_name): (POINT2D, &str)| {
+        let mut azimuth = 0.0;
+        if unsafe { azimuth_pt_pt(&from, &to, &mut azimuth) } == LW_FALSE as c_int {
+            return Err(LWGeomError::InvalidParameterError(
+                "ST_Angle".to_owned(),
+                format!("{from_name}, {to_name}"),
+            ));
+        }
+        Ok(azimuth)
+    };
+
+    let (a1, a2, a3) = (
+        (point(p1, "p1")?, "p1"),
+        (point(p2, "p2")?, "p2"),
+        (point(p3, "p3")?, "p3"),
+    );
+    let (azimuth1, azimuth2) = match p4 {
+        Some(p4) => {
+            let a4 = (point(p4, "p4")?, "p4");
+            (azimuth(a1, a2)?, azimuth(a3, a4)?)
+        }
+        None => (azimuth(a2, a1)?, azimuth(a2, a3)?),
+    };
+
+    let angle = azimuth2 - azimuth1;
+    Ok(if angle < 0.0 {
+        angle + 2.0 * core::f64::consts::PI
+    } else {
+        angle
+    })
 }
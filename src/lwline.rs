@@ -0,0 +1,38 @@
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+
+use lwgeom_sys::*;
+
+pub struct LWLineRef(PhantomData<UnsafeCell<*mut LWLINE>>);
+
+impl LWLineRef {
+    pub fn from_ptr<'a>(ptr: *mut LWLINE) -> &'a Self {
+        debug_assert!(
+            !ptr.is_null(),
+            "Attempted to create a LWLineRef from a null pointer."
+        );
+        unsafe { &*(ptr as *mut _) }
+    }
+
+    fn as_ptr(&self) -> *mut LWLINE {
+        self as *const _ as *mut _
+    }
+
+    fn as_ref(&self) -> &LWLINE {
+        unsafe { &*self.as_ptr().cast_const() }
+    }
+}
+
+unsafe impl Send for LWLineRef {}
+unsafe impl Sync for LWLineRef {}
+
+impl LWLineRef {
+    pub fn num_points(&self) -> usize {
+        let p_points = self.as_ref().points;
+        if p_points.is_null() {
+            0
+        } else {
+            unsafe { (*p_points).npoints as usize }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+
+use libc::c_int;
+use lwgeom_sys::*;
+
+pub struct LWPointRef(PhantomData<UnsafeCell<*mut LWPOINT>>);
+
+impl LWPointRef {
+    pub fn from_ptr<'a>(ptr: *mut LWPOINT) -> &'a Self {
+        debug_assert!(
+            !ptr.is_null(),
+            "Attempted to create a LWPointRef from a null pointer."
+        );
+        unsafe { &*(ptr as *mut _) }
+    }
+
+    fn as_ptr(&self) -> *mut LWPOINT {
+        self as *const _ as *mut _
+    }
+
+    fn as_ref(&self) -> &LWPOINT {
+        unsafe { &*self.as_ptr().cast_const() }
+    }
+}
+
+unsafe impl Send for LWPointRef {}
+unsafe impl Sync for LWPointRef {}
+
+impl LWPointRef {
+    pub fn is_empty(&self) -> bool {
+        let p_point = self.as_ref().point;
+        p_point.is_null() || unsafe { (*p_point).npoints == 0 }
+    }
+
+    pub fn has_z(&self) -> bool {
+        self.as_ref().flags as u32 & LWFLAG_Z != 0
+    }
+
+    pub fn has_m(&self) -> bool {
+        self.as_ref().flags as u32 & LWFLAG_M != 0
+    }
+
+    pub fn x(&self) -> Option<f64> {
+        self.point().map(|point| point.x)
+    }
+
+    pub fn y(&self) -> Option<f64> {
+        self.point().map(|point| point.y)
+    }
+
+    pub fn z(&self) -> Option<f64> {
+        self.point().filter(|_| self.has_z()).map(|point| point.z)
+    }
+
+    pub fn m(&self) -> Option<f64> {
+        self.point().filter(|_| self.has_m()).map(|point| point.m)
+    }
+
+    fn point(&self) -> Option<POINT4D> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let mut point = MaybeUninit::uninit();
+        if unsafe { lwpoint_getPoint4d_p(self.as_ptr(), point.as_mut_ptr()) } == LW_FAILURE as c_int
+        {
+            return None;
+        }
+        Some(unsafe { point.assume_init() })
+    }
+}
@@ -1,6 +1,9 @@
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+
 use lwgeom_sys::*;
 
-use crate::LWGeom;
+use crate::{LWGeom, lwerror};
 
 pub struct LWPoly(*mut LWPOLY);
 
@@ -10,6 +13,7 @@ impl LWPoly {
             !ptr.is_null(),
             "Attempted to create a LWPoly from a null pointer."
         );
+        lwerror::install();
         Self(ptr)
     }
 
@@ -33,6 +37,29 @@ impl Drop for LWPoly {
     }
 }
 
+pub struct LWPolyRef(PhantomData<UnsafeCell<*mut LWPOLY>>);
+
+impl LWPolyRef {
+    pub fn from_ptr<'a>(ptr: *mut LWPOLY) -> &'a Self {
+        debug_assert!(
+            !ptr.is_null(),
+            "Attempted to create a LWPolyRef from a null pointer."
+        );
+        unsafe { &*(ptr as *mut _) }
+    }
+
+    fn as_ptr(&self) -> *mut LWPOLY {
+        self as *const _ as *mut _
+    }
+
+    fn as_ref(&self) -> &LWPOLY {
+        unsafe { &*self.as_ptr().cast_const() }
+    }
+}
+
+unsafe impl Send for LWPolyRef {}
+unsafe impl Sync for LWPolyRef {}
+
 impl LWPoly {
     pub fn into_lwgeom(self) -> LWGeom {
         let p_geom = unsafe { lwpoly_as_lwgeom(self.into_ptr()) };
@@ -40,7 +67,14 @@ impl LWPoly {
     }
 
     pub fn construct_envelope(srid: i32, x1: f64, y1: f64, x2: f64, y2: f64) -> Self {
+        lwerror::install();
         let p_poly = unsafe { lwpoly_construct_envelope(srid, x1, y1, x2, y2) };
         Self::from_ptr(p_poly)
     }
 }
+
+impl LWPolyRef {
+    pub fn num_rings(&self) -> usize {
+        self.as_ref().nrings as usize
+    }
+}
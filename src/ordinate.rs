@@ -0,0 +1,20 @@
+use lwgeom_sys::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ordinate {
+    X,
+    Y,
+    Z,
+    M,
+}
+
+impl Ordinate {
+    pub(crate) fn to_lword(self) -> LWORD {
+        match self {
+            Ordinate::X => LWORD_T_LWORD_X,
+            Ordinate::Y => LWORD_T_LWORD_Y,
+            Ordinate::Z => LWORD_T_LWORD_Z,
+            Ordinate::M => LWORD_T_LWORD_M,
+        }
+    }
+}
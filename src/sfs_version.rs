@@ -0,0 +1,8 @@
+/// Simple Features revision targeted by `force_sfs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SfsVersion {
+    /// SFS 1.1, which also rewrites triangles, TINs and polyhedral surfaces.
+    V1_1 = 110,
+    /// SFS 1.2, which only linearizes curves.
+    V1_2 = 120,
+}
@@ -0,0 +1,60 @@
+use core::mem::MaybeUninit;
+
+use lwgeom_sys::*;
+
+use crate::lwerror;
+
+pub struct Spheroid(SPHEROID);
+
+impl Spheroid {
+    /// Spheroid with semi-major axis `a` and semi-minor axis `b` in meters.
+    pub fn new(a: f64, b: f64) -> Self {
+        lwerror::install();
+        let mut spheroid = MaybeUninit::zeroed();
+        unsafe {
+            spheroid_init(spheroid.as_mut_ptr(), a, b);
+            Spheroid(spheroid.assume_init())
+        }
+    }
+
+    pub fn wgs84() -> Self {
+        let a = 6378137.0;
+        Self::new(a, a - a / 298.257223563)
+    }
+
+    pub(crate) fn as_ptr(&self) -> *const SPHEROID {
+        &self.0
+    }
+
+    pub fn a(&self) -> f64 {
+        self.0.a
+    }
+
+    pub fn b(&self) -> f64 {
+        self.0.b
+    }
+
+    /// Mean radius `(2a + b) / 3`, used by the spherical distance
+    /// calculations.
+    pub fn radius(&self) -> f64 {
+        self.0.radius
+    }
+
+    /// Radius of the sphere with the same surface area,
+    /// `sqrt((a² + b² / e · atanh(e)) / 2)` for eccentricity `e`.
+    pub fn authalic_radius(&self) -> f64 {
+        let (a, b, e) = (self.0.a, self.0.b, self.0.e);
+        if e == 0.0 {
+            return a;
+        }
+        ((a * a + b * b / e * e.atanh()) / 2.0).sqrt()
+    }
+
+    /// Copy whose sphere radius is the authalic one, for spherical areas.
+    pub(crate) fn authalic(&self) -> Self {
+        Spheroid(SPHEROID {
+            radius: self.authalic_radius(),
+            ..self.0
+        })
+    }
+}
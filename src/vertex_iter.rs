@@ -0,0 +1,62 @@
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+
+use libc::c_int;
+use lwgeom_sys::*;
+
+use crate::LWGeomRef;
+
+/// Walks every vertex of a geometry in storage order, yielding
+/// `[x, y, z, m]` with NaN for ordinates the geometry does not have.
+pub struct VertexIter<'a> {
+    iter: *mut LWPOINTITERATOR,
+    has_z: bool,
+    has_m: bool,
+    remaining: usize,
+    geom: PhantomData<&'a LWGeomRef>,
+}
+
+impl<'a> VertexIter<'a> {
+    pub(crate) fn new(geom: &'a LWGeomRef) -> Self {
+        let p_geom = geom as *const LWGeomRef as *const LWGEOM;
+        Self {
+            iter: unsafe { lwpointiterator_create(p_geom) },
+            has_z: geom.has_z(),
+            has_m: geom.has_m(),
+            remaining: unsafe { lwgeom_count_vertices(p_geom) } as usize,
+            geom: PhantomData,
+        }
+    }
+}
+
+impl Iterator for VertexIter<'_> {
+    type Item = [f64; 4];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut point = MaybeUninit::uninit();
+        if unsafe { lwpointiterator_next(self.iter, point.as_mut_ptr()) } != LW_SUCCESS as c_int {
+            return None;
+        }
+
+        let point: POINT4D = unsafe { point.assume_init() };
+        self.remaining = self.remaining.saturating_sub(1);
+        Some([
+            point.x,
+            point.y,
+            if self.has_z { point.z } else { f64::NAN },
+            if self.has_m { point.m } else { f64::NAN },
+        ])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for VertexIter<'_> {}
+
+impl Drop for VertexIter<'_> {
+    fn drop(&mut self) {
+        unsafe { lwpointiterator_destroy(self.iter) }
+    }
+}